@@ -0,0 +1,127 @@
+use crate::cube::{CubieLevel, Move};
+use crate::notation::{format_moves, parse_moves, ParseMoveError};
+use std::fmt;
+use std::ops::{Add, Deref};
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Algorithm(pub Vec<Move>);
+
+impl Algorithm {
+    pub fn new() -> Self {
+        Algorithm(vec![])
+    }
+
+    pub fn inverse(&self) -> Algorithm {
+        Algorithm(self.0.iter().rev().map(|m| m.inverse()).collect())
+    }
+
+    pub fn apply(&self, cube: &CubieLevel) -> CubieLevel {
+        cube.apply_all(&self.0)
+    }
+
+    // the number of repetitions returning to the start
+    pub fn order(&self) -> usize {
+        CubieLevel::from_scramble(&self.0)
+            .order()
+            .expect("moves only reach valid cubes")
+    }
+
+    // [A, B] = A B A' B'
+    pub fn commutator(&self, other: &Algorithm) -> Algorithm {
+        &(self + other) + &(&self.inverse() + &other.inverse())
+    }
+
+    // A B A'
+    pub fn conjugate(&self, other: &Algorithm) -> Algorithm {
+        &(self + other) + &self.inverse()
+    }
+}
+
+impl Deref for Algorithm {
+    type Target = [Move];
+
+    fn deref(&self) -> &[Move] {
+        &self.0
+    }
+}
+
+impl From<Vec<Move>> for Algorithm {
+    fn from(src: Vec<Move>) -> Self {
+        Algorithm(src)
+    }
+}
+
+impl From<Algorithm> for Vec<Move> {
+    fn from(src: Algorithm) -> Self {
+        src.0
+    }
+}
+
+impl std::iter::FromIterator<Move> for Algorithm {
+    fn from_iter<I: IntoIterator<Item = Move>>(iter: I) -> Self {
+        Algorithm(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for Algorithm {
+    type Item = Move;
+    type IntoIter = std::vec::IntoIter<Move>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> Add<&'a Algorithm> for &'a Algorithm {
+    type Output = Algorithm;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        self.0.iter().chain(rhs.0.iter()).cloned().collect()
+    }
+}
+
+impl Add<Algorithm> for Algorithm {
+    type Output = Algorithm;
+
+    fn add(mut self, rhs: Algorithm) -> Self::Output {
+        self.0.extend(rhs.0);
+        self
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        parse_moves(s).map(Algorithm)
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format_moves(&self.0))
+    }
+}
+
+#[test]
+fn algorithm() {
+    use crate::cube::SOLVED;
+
+    let scramble: Algorithm = "R U R' U' F2 D B'".parse().unwrap();
+    assert_eq!(scramble.inverse().to_string(), "B D' F2 U R U' R'");
+
+    let cube = CubieLevel::from_scramble(&scramble);
+    assert_ne!(cube, SOLVED);
+    assert_eq!(cube.apply_all(&scramble.inverse()), SOLVED);
+    assert_eq!((&scramble + &scramble.inverse()).apply(&SOLVED), SOLVED);
+    assert_eq!(
+        SOLVED.apply(Move::R1).apply(Move::U1),
+        CubieLevel::from_scramble(&[Move::R1, Move::U1])
+    );
+
+    let (r, u): (Algorithm, Algorithm) = ("R".parse().unwrap(), "U".parse().unwrap());
+    assert_eq!(r.commutator(&u).to_string(), "R U R' U'");
+    assert_eq!(r.conjugate(&u).to_string(), "R U R'");
+    assert_eq!(r.commutator(&u).order(), 6);
+}
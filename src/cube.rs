@@ -5,6 +5,8 @@ use strum::IntoEnumIterator;
 use CornerCube as CC;
 use EdgeCube as EC;
 
+const FACT12: u128 = 479_001_600;
+
 #[rustfmt::skip]
 pub const SOLVED: CubieLevel = CubieLevel(
     [
@@ -34,30 +36,33 @@ pub const SOLVED: CubieLevel = CubieLevel(
 );
 
 #[rustfmt::skip]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum CornerCubePos {
     URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB,
 }
 
 #[rustfmt::skip]
-#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub enum EdgeCubePos {
     UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CornerCube {
     pub c: CornerCubePos,
     pub o: u8, // [0, 3)
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct EdgeCube {
     pub e: EdgeCubePos,
     pub o: u8, // [0, 2)
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumCount, EnumIter)]
+#[derive(
+    Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize,
+    EnumCount, EnumIter, FromPrimitive, ToPrimitive,
+)]
 #[rustfmt::skip]
 pub enum Move {
     U1, U2, U3,
@@ -68,9 +73,222 @@ pub enum Move {
     R1, R2, R3,
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+impl Move {
+    pub fn face(self) -> usize {
+        self as usize / 3
+    }
+
+    pub fn axis(self) -> usize {
+        self.face() / 2
+    }
+
+    pub fn inverse(self) -> Move {
+        Move::from_usize(self.face() * 3 + 2 - self as usize % 3).unwrap()
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 pub struct CubieLevel(pub [CornerCube; 8], pub [EdgeCube; 12]);
 
+impl CubieLevel {
+    pub fn apply(&self, m: Move) -> CubieLevel {
+        m * *self
+    }
+
+    pub fn apply_all(&self, moves: &[Move]) -> CubieLevel {
+        moves.iter().fold(*self, |c, &m| m * c)
+    }
+
+    pub fn from_scramble(moves: &[Move]) -> CubieLevel {
+        SOLVED.apply_all(moves)
+    }
+
+    pub fn inverse(&self) -> CubieLevel {
+        self.inv()
+    }
+
+    // self applied n times; negative n applies the inverse
+    pub fn pow(&self, n: i32) -> CubieLevel {
+        let mut base = if n < 0 { self.inv() } else { *self };
+        let mut n = n.unsigned_abs();
+        let mut res = SOLVED;
+        while n > 0 {
+            if n & 1 == 1 {
+                res = &res * &base;
+            }
+            base = &base * &base;
+            n >>= 1;
+        }
+        res
+    }
+
+    // the smallest n > 0 with self^n solved, which is at most 1260 for a valid cube;
+    // a cube that is not a permutation, e.g. with a cubie twice, never returns to solved
+    pub fn order(&self) -> Result<usize, VerifyError> {
+        self.verify()?;
+        let mut cur = *self;
+        let mut res = 1;
+        while cur != SOLVED {
+            cur = &cur * self;
+            res += 1;
+        }
+        Ok(res)
+    }
+
+    // [A, B] = A B A' B'
+    pub fn commutator(&self, other: &CubieLevel) -> CubieLevel {
+        let res = &self.inv() * &(other * self);
+        &other.inv() * &res
+    }
+
+    // A B A'
+    pub fn conjugate(&self, other: &CubieLevel) -> CubieLevel {
+        &self.inv() * &(other * self)
+    }
+
+    pub fn verify(&self) -> Result<(), VerifyError> {
+        let mut seen = [false; 8];
+        for (i, c) in self.0.iter().enumerate() {
+            if c.o >= 3 {
+                return Err(VerifyError::CornerOrientation(SOLVED.0[i].c));
+            }
+            if seen[c.c as usize] {
+                return Err(VerifyError::DuplicateCorner(c.c));
+            }
+            seen[c.c as usize] = true;
+        }
+
+        let mut seen = [false; 12];
+        for (i, e) in self.1.iter().enumerate() {
+            if e.o >= 2 {
+                return Err(VerifyError::EdgeOrientation(SOLVED.1[i].e));
+            }
+            if seen[e.e as usize] {
+                return Err(VerifyError::DuplicateEdge(e.e));
+            }
+            seen[e.e as usize] = true;
+        }
+
+        if self.0.iter().map(|c| c.o as usize).sum::<usize>() % 3 != 0 {
+            return Err(VerifyError::CornerTwistSum);
+        }
+        if self.1.iter().map(|e| e.o as usize).sum::<usize>() % 2 != 0 {
+            return Err(VerifyError::EdgeFlipSum);
+        }
+
+        fn parity(perm: &[usize]) -> usize {
+            let mut res = 0;
+            for i in 0..perm.len() {
+                res += perm[i + 1..].iter().filter(|&&x| x < perm[i]).count();
+            }
+            res % 2
+        }
+        let cp: Vec<_> = self.0.iter().map(|c| c.c as usize).collect();
+        let ep: Vec<_> = self.1.iter().map(|e| e.e as usize).collect();
+        if parity(&cp) != parity(&ep) {
+            return Err(VerifyError::ParityMismatch);
+        }
+
+        Ok(())
+    }
+
+    // the smallest of the conjugates by Sym48 and those of the inverse
+    pub fn canonical(&self) -> CubieLevel {
+        let inv = self.inv();
+        Sym48::iter()
+            .flat_map(|s| vec![s * *self, s * inv])
+            .min()
+            .unwrap()
+    }
+
+    pub fn is_equivalent(&self, other: &CubieLevel) -> bool {
+        self.canonical() == other.canonical()
+    }
+
+    // corner permutation, twist, flip and edge permutation in mixed radix;
+    // the 4.3e19 reachable states do not fit in a u64
+    pub fn pack(&self) -> u128 {
+        use crate::solve::phase1::{ctwist::CTwist, etwist::ETwist, CTWIST_COUNT, ETWIST_COUNT};
+        use crate::solve::phase2::cperm::CPerm;
+        use crate::solve::util::FisherShuffle;
+
+        let ep: Vec<_> = self.1.iter().map(|e| e.e as usize).collect();
+        let ep = FisherShuffle::new(12).array_to_num(&ep) as u128;
+        let cp = u128::from(CPerm::from(*self).0);
+        let tw = u128::from(CTwist::from(*self).0);
+        let fl = u128::from(ETwist::from(*self).0);
+
+        ((cp * CTWIST_COUNT as u128 + tw) * ETWIST_COUNT as u128 + fl) * FACT12 + ep
+    }
+
+    // None unless src packs a reachable state
+    pub fn unpack(src: u128) -> Option<CubieLevel> {
+        use crate::solve::phase1::{ctwist::CTwist, etwist::ETwist, CTWIST_COUNT, ETWIST_COUNT};
+        use crate::solve::phase2::{cperm::CPerm, CPERM_COUNT};
+        use crate::solve::util::FisherShuffle;
+
+        let (ep, src) = (src % FACT12, src / FACT12);
+        let (fl, src) = (src % ETWIST_COUNT as u128, src / ETWIST_COUNT as u128);
+        let (tw, cp) = (src % CTWIST_COUNT as u128, src / CTWIST_COUNT as u128);
+        if cp >= CPERM_COUNT as u128 {
+            return None;
+        }
+
+        let corners = CubieLevel::from(CPerm(cp as u16));
+        let twist = CubieLevel::from(CTwist(tw as u16));
+        let flip = CubieLevel::from(ETwist(fl as u16));
+        let ep = FisherShuffle::new(12).num_to_array(ep as usize);
+
+        let mut res = SOLVED;
+        for i in 0..8 {
+            res.0[i] = CC {
+                c: corners.0[i].c,
+                o: twist.0[i].o,
+            };
+        }
+        for (i, &e) in ep.iter().enumerate() {
+            res.1[i] = EC {
+                e: SOLVED.1[e].e,
+                o: flip.1[i].o,
+            };
+        }
+        Some(res).filter(|res| res.verify().is_ok())
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VerifyError {
+    CornerOrientation(CornerCubePos),
+    EdgeOrientation(EdgeCubePos),
+    DuplicateCorner(CornerCubePos),
+    DuplicateEdge(EdgeCubePos),
+    CornerTwistSum,
+    EdgeFlipSum,
+    ParityMismatch,
+}
+
+impl std::fmt::Display for VerifyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            VerifyError::CornerOrientation(pos) => {
+                write!(f, "corner at {:?} has an invalid orientation", pos)
+            }
+            VerifyError::EdgeOrientation(pos) => {
+                write!(f, "edge at {:?} has an invalid orientation", pos)
+            }
+            VerifyError::DuplicateCorner(c) => write!(f, "corner {:?} appears twice", c),
+            VerifyError::DuplicateEdge(e) => write!(f, "edge {:?} appears twice", e),
+            VerifyError::CornerTwistSum => write!(f, "a corner is twisted"),
+            VerifyError::EdgeFlipSum => write!(f, "an edge is flipped"),
+            VerifyError::ParityMismatch => {
+                write!(f, "corner and edge permutation parities differ")
+            }
+        }
+    }
+}
+
+impl std::error::Error for VerifyError {}
+
 impl<'a> Mul<&'a CubieLevel> for &'a CubieLevel {
     type Output = CubieLevel;
 
@@ -80,10 +298,16 @@ impl<'a> Mul<&'a CubieLevel> for &'a CubieLevel {
 
         let mut res = SOLVED;
 
+        // orientations >= 3 are mirrored, only appearing in symmetries
         for i in 0..8 {
             res.0[i] = rhs.0[lhs.0[i].c as usize];
-            res.0[i].o += lhs.0[i].o;
-            res.0[i].o %= 3;
+            let (a, b) = (res.0[i].o, lhs.0[i].o);
+            res.0[i].o = match (a < 3, b < 3) {
+                (true, true) => (a + b) % 3,
+                (true, false) => (a + b) % 3 + 3,
+                (false, true) => (a + 3 - b) % 3 + 3,
+                (false, false) => (a + 3 - b) % 3,
+            };
         }
 
         for i in 0..12 {
@@ -96,6 +320,25 @@ impl<'a> Mul<&'a CubieLevel> for &'a CubieLevel {
     }
 }
 
+impl Inv for CubieLevel {
+    fn inv(self) -> Self {
+        let mut res = SOLVED;
+        for (i, c) in self.0.iter().enumerate() {
+            res.0[c.c as usize] = CC {
+                c: SOLVED.0[i].c,
+                o: if c.o < 3 { (3 - c.o) % 3 } else { c.o },
+            };
+        }
+        for (i, e) in self.1.iter().enumerate() {
+            res.1[e.e as usize] = EC {
+                e: SOLVED.1[i].e,
+                o: e.o,
+            };
+        }
+        res
+    }
+}
+
 impl Mul<CubieLevel> for Move {
     type Output = CubieLevel;
 
@@ -312,6 +555,116 @@ impl Mul<CubieLevel> for Move {
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn verify() {
+        use super::*;
+
+        let cube = CubieLevel::from_scramble(&[Move::R1, Move::U3, Move::F2, Move::B1]);
+        assert_eq!(cube.verify(), Ok(()));
+
+        let mut c = cube;
+        c.0[3].o = (c.0[3].o + 1) % 3;
+        assert_eq!(c.verify(), Err(VerifyError::CornerTwistSum));
+
+        let mut c = cube;
+        c.1[5].o ^= 1;
+        assert_eq!(c.verify(), Err(VerifyError::EdgeFlipSum));
+
+        let mut c = cube;
+        c.1.swap(0, 1);
+        assert_eq!(c.verify(), Err(VerifyError::ParityMismatch));
+
+        let mut c = cube;
+        c.0[2].c = c.0[6].c;
+        assert_eq!(c.verify(), Err(VerifyError::DuplicateCorner(c.0[6].c)));
+
+        let mut c = cube;
+        c.1[4].e = c.1[0].e;
+        assert_eq!(c.verify(), Err(VerifyError::DuplicateEdge(c.1[0].e)));
+    }
+
+    #[test]
+    fn canonical() {
+        use super::*;
+
+        let cube = CubieLevel::from_scramble(&[Move::R1, Move::U3, Move::F2, Move::B1]);
+        assert_eq!(&cube * &cube.inv(), SOLVED);
+        for s in Sym48::iter() {
+            assert_eq!(&CubieLevel::from(s) * &CubieLevel::from(s).inv(), SOLVED);
+        }
+        assert_eq!(SOLVED.canonical(), SOLVED);
+
+        let canonical = cube.canonical();
+        for s in Sym48::iter() {
+            assert_eq!((s * cube).canonical(), canonical);
+            assert_eq!((s * cube.inv()).canonical(), canonical);
+        }
+
+        let r1 = SOLVED.apply(Move::R1);
+        assert!(r1.is_equivalent(&SOLVED.apply(Move::U3)));
+        assert!(r1.is_equivalent(&SOLVED.apply(Move::L1)));
+        assert!(!r1.is_equivalent(&SOLVED.apply(Move::R2)));
+        assert!(!cube.is_equivalent(&r1));
+    }
+
+    #[test]
+    fn algebra() {
+        use super::*;
+        use Move::*;
+
+        let sexy = CubieLevel::from_scramble(&[R1, U1, R3, U3]);
+        assert_eq!(sexy.order(), Ok(6));
+        assert_eq!(sexy.pow(6), SOLVED);
+        assert_eq!(sexy.pow(-1), sexy.inverse());
+        assert_eq!(sexy.pow(7), sexy);
+        assert_eq!(SOLVED.order(), Ok(1));
+        assert_eq!(CubieLevel::from_scramble(&[R1, U1]).order(), Ok(105));
+        let mut dup = SOLVED;
+        dup.0[0].c = dup.0[1].c;
+        assert!(dup.order().is_err());
+
+        let (r, u) = (SOLVED.apply(R1), SOLVED.apply(U1));
+        assert_eq!(r.commutator(&u), sexy);
+        assert_eq!(r.conjugate(&u), CubieLevel::from_scramble(&[R1, U1, R3]));
+    }
+
+    #[test]
+    fn pack() {
+        use super::*;
+        use rand::SeedableRng;
+        use std::collections::HashMap;
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+        let cubes: Vec<_> = (0..100)
+            .map(|_| crate::scramble::random_cube(&mut rng))
+            .collect();
+
+        let mut packed = HashMap::new();
+        for cube in cubes.iter() {
+            assert_eq!(CubieLevel::unpack(cube.pack()), Some(*cube));
+            packed.insert(*cube, cube.pack());
+        }
+        assert_eq!(packed.len(), cubes.len());
+        assert_eq!(CubieLevel::unpack(!0), None);
+        // every field in range, but the permutations of opposite parity
+        let mut odd = SOLVED;
+        odd.1.swap(0, 1);
+        assert_eq!(odd.verify(), Err(VerifyError::ParityMismatch));
+        assert_eq!(CubieLevel::unpack(odd.pack()), None);
+
+        let mut sorted = cubes.clone();
+        sorted.sort();
+        assert_eq!(sorted[0], *cubes.iter().min().unwrap());
+
+        let bytes = bincode::serialize(&cubes).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Vec<CubieLevel>>(&bytes).unwrap(),
+            cubes
+        );
+        let bytes = bincode::serialize(&Move::R3).unwrap();
+        assert_eq!(bincode::deserialize::<Move>(&bytes).unwrap(), Move::R3);
+    }
+
     #[test]
     fn jimei() {
         use super::Move::*;
@@ -417,14 +770,14 @@ impl From<SymU> for &'static CubieLevel {
                 CC { c: DBL, o: 0 },
             ],
             [
-                EC { e: UB, o: 1 },
-                EC { e: UR, o: 1 },
-                EC { e: UF, o: 1 },
-                EC { e: UL, o: 1 },
-                EC { e: DB, o: 1 },
-                EC { e: DR, o: 1 },
-                EC { e: DF, o: 1 },
-                EC { e: DL, o: 1 },
+                EC { e: UB, o: 0 },
+                EC { e: UR, o: 0 },
+                EC { e: UF, o: 0 },
+                EC { e: UL, o: 0 },
+                EC { e: DB, o: 0 },
+                EC { e: DR, o: 0 },
+                EC { e: DF, o: 0 },
+                EC { e: DL, o: 0 },
                 EC { e: BR, o: 1 },
                 EC { e: FR, o: 1 },
                 EC { e: FL, o: 1 },
@@ -483,7 +836,7 @@ impl From<SymLR> for &'static CubieLevel {
     }
 }
 
-trait Inv {
+pub trait Inv {
     fn inv(self) -> Self;
 }
 
@@ -855,3 +1208,239 @@ impl Mul<Move> for Sym16 {
         MEMO[self.0 as usize * MOVE_COUNT + rhs as usize]
     }
 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, EnumCount, EnumIter, FromPrimitive)]
+#[rustfmt::skip]
+enum SymURF { URF0, URF1, URF2 }
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+struct Sym48Vec(SymURF, Sym16);
+
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct Sym48(pub u8);
+pub const SYM48_COUNT: usize = 48;
+
+impl std::fmt::Debug for Sym48 {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> Result<(), std::fmt::Error> {
+        Sym48Vec::fmt(&Sym48Vec::from(*self), f)
+    }
+}
+
+impl From<SymURF> for &'static CubieLevel {
+    fn from(src: SymURF) -> &'static CubieLevel {
+        use CornerCubePos::*;
+        use EdgeCubePos::*;
+
+        // 120 degrees around the URF-DBL diagonal
+        const URF1_CUBE: CubieLevel = CubieLevel(
+            [
+                CC { c: URF, o: 1 },
+                CC { c: DFR, o: 2 },
+                CC { c: DLF, o: 1 },
+                CC { c: UFL, o: 2 },
+                CC { c: UBR, o: 2 },
+                CC { c: DRB, o: 1 },
+                CC { c: DBL, o: 2 },
+                CC { c: ULB, o: 1 },
+            ],
+            [
+                EC { e: UF, o: 1 },
+                EC { e: FR, o: 0 },
+                EC { e: DF, o: 1 },
+                EC { e: FL, o: 0 },
+                EC { e: UB, o: 1 },
+                EC { e: BR, o: 0 },
+                EC { e: DB, o: 1 },
+                EC { e: BL, o: 0 },
+                EC { e: UR, o: 1 },
+                EC { e: DR, o: 1 },
+                EC { e: DL, o: 1 },
+                EC { e: UL, o: 1 },
+            ],
+        );
+        lazy_static! {
+            static ref URF2_CUBE: CubieLevel = &URF1_CUBE * &URF1_CUBE;
+        };
+
+        match src {
+            SymURF::URF0 => &SOLVED,
+            SymURF::URF1 => &URF1_CUBE,
+            SymURF::URF2 => &URF2_CUBE,
+        }
+    }
+}
+
+// s * c == X^-1 * c * X where X is the cube of s
+impl From<Sym48> for CubieLevel {
+    fn from(src: Sym48) -> Self {
+        let Sym48Vec(urf, s) = src.into();
+        let Sym16Vec(f, u, lr) = s.into();
+        let res = <&CubieLevel>::from(lr) * <&CubieLevel>::from(u);
+        let res = &res * <&CubieLevel>::from(f);
+        &res * <&CubieLevel>::from(urf)
+    }
+}
+
+impl From<Sym16> for Sym48 {
+    fn from(src: Sym16) -> Self {
+        Sym48(src.0)
+    }
+}
+
+impl From<Sym48Vec> for Sym48 {
+    fn from(src: Sym48Vec) -> Self {
+        let Sym48Vec(urf, s) = src;
+        Sym48(urf as u8 * SYM16_COUNT as u8 + s.0)
+    }
+}
+impl From<Sym48> for Sym48Vec {
+    fn from(src: Sym48) -> Self {
+        let urf = SymURF::from_u8(src.0 / SYM16_COUNT as u8).unwrap();
+        Sym48Vec(urf, Sym16(src.0 % SYM16_COUNT as u8))
+    }
+}
+
+impl Sym48 {
+    fn cube(self) -> &'static CubieLevel {
+        lazy_static! {
+            static ref MEMO: Vec<CubieLevel> = Sym48::iter().map(CubieLevel::from).collect();
+        }
+        &MEMO[self.0 as usize]
+    }
+}
+
+impl Inv for Sym48 {
+    fn inv(self) -> Self {
+        lazy_static! {
+            static ref MEMO: Vec<Sym48> = Sym48::iter()
+                .map(|s| {
+                    Sym48::iter()
+                        .find(|&t| t.cube() * s.cube() == SOLVED)
+                        .unwrap()
+                })
+                .collect();
+        }
+        MEMO[self.0 as usize]
+    }
+}
+
+impl Mul<Sym48> for Sym48 {
+    type Output = Sym48;
+
+    fn mul(self, rhs: Sym48) -> Self::Output {
+        lazy_static! {
+            static ref MEMO: Vec<Sym48> = {
+                let mut memo = vec![Sym48(!0); SYM48_COUNT * SYM48_COUNT];
+
+                for s1 in Sym48::iter() {
+                    for s2 in Sym48::iter() {
+                        let r = s1.cube() * s2.cube();
+
+                        memo[s2.0 as usize * SYM48_COUNT + s1.0 as usize] =
+                            Sym48::iter().find(|&s| *s.cube() == r).unwrap();
+                    }
+                }
+                memo
+            };
+        }
+        MEMO[self.0 as usize * SYM48_COUNT + rhs.0 as usize]
+    }
+}
+
+impl Mul<CubieLevel> for Sym48 {
+    type Output = CubieLevel;
+
+    fn mul(self, rhs: CubieLevel) -> Self::Output {
+        let res = &rhs * self.cube();
+        self.inv().cube() * &res
+    }
+}
+
+impl Mul<Move> for Sym48 {
+    type Output = Move;
+
+    fn mul(self, rhs: Move) -> Self::Output {
+        lazy_static! {
+            static ref MEMO: Vec<Move> = {
+                let mut res = Vec::with_capacity(SYM48_COUNT * MOVE_COUNT);
+                for s in Sym48::iter() {
+                    for m in Move::iter() {
+                        let c = s * (m * SOLVED);
+                        res.push(Move::iter().find(|&m| m * SOLVED == c).unwrap());
+                    }
+                }
+                res
+            };
+        }
+        MEMO[self.0 as usize * MOVE_COUNT + rhs as usize]
+    }
+}
+
+pub struct Sym48Iterator(u8);
+impl Sym48 {
+    pub fn iter() -> Sym48Iterator {
+        Sym48Iterator(0)
+    }
+}
+impl std::iter::Iterator for Sym48Iterator {
+    type Item = Sym48;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let cur = self.0;
+        if cur < SYM48_COUNT as u8 {
+            self.0 += 1;
+            return Some(Sym48(cur));
+        }
+        None
+    }
+}
+
+#[test]
+fn sym48() {
+    let cube = CubieLevel::from_scramble(&[Move::R1, Move::U3, Move::F1, Move::D2, Move::B3]);
+
+    for s1 in Sym48::iter() {
+        assert_eq!(s1 * s1.inv(), Sym48(0));
+        assert_eq!(s1.inv() * (s1 * cube), cube);
+        assert_eq!((s1 * cube).verify(), Ok(()));
+        if s1.0 < SYM16_COUNT as u8 {
+            assert_eq!(s1 * cube, Sym16(s1.0) * cube);
+        }
+
+        // conjugation is a homomorphism: s (m c) = (s m) (s c)
+        for m in Move::iter() {
+            assert_eq!(s1 * (m * cube), (s1 * m) * (s1 * cube), "{:?} {:?}", s1, m);
+        }
+        for s2 in Sym48::iter() {
+            assert_eq!(s1 * (s2 * cube), (s1 * s2) * cube, "{:?} {:?}", s1, s2);
+        }
+    }
+
+    let mut images: Vec<CubieLevel> = vec![];
+    for s in Sym48::iter() {
+        if !images.contains(&(s * cube)) {
+            images.push(s * cube);
+        }
+    }
+    assert_eq!(images.len(), SYM48_COUNT);
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Sym1; // identity only; for coordinates without symmetry reduction
+pub const SYM1_COUNT: usize = 1;
+
+impl Mul<CubieLevel> for Sym1 {
+    type Output = CubieLevel;
+
+    fn mul(self, rhs: CubieLevel) -> Self::Output {
+        rhs
+    }
+}
+
+impl Mul<Move> for Sym1 {
+    type Output = Option<Move>;
+
+    fn mul(self, rhs: Move) -> Self::Output {
+        Some(rhs)
+    }
+}
@@ -0,0 +1,243 @@
+use crate::cube::{CornerCube, CornerCubePos, CubieLevel, EdgeCube, EdgeCubePos, SOLVED};
+use std::fmt;
+use std::str::FromStr;
+
+#[rustfmt::skip]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, EnumCount, EnumIter)]
+pub enum Face {
+    U, R, F, D, L, B,
+}
+
+const CENTERS: [Face; 6] = [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+// U1..U9, R1..R9, F1..F9, D1..D9, L1..L9, B1..B9
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub struct FaceletCube(pub [Face; 54]);
+
+#[rustfmt::skip]
+pub const CORNER_FACELETS: [[usize; 3]; 8] = [
+    [8, 9, 20],   // URF: U9 R1 F3
+    [6, 18, 38],  // UFL: U7 F1 L3
+    [0, 36, 47],  // ULB: U1 L1 B3
+    [2, 45, 11],  // UBR: U3 B1 R3
+    [29, 26, 15], // DFR: D3 F9 R7
+    [27, 44, 24], // DLF: D1 L9 F7
+    [33, 53, 42], // DBL: D7 B9 L7
+    [35, 17, 51], // DRB: D9 R9 B7
+];
+
+#[rustfmt::skip]
+pub const EDGE_FACELETS: [[usize; 2]; 12] = [
+    [5, 10],  // UR
+    [7, 19],  // UF
+    [3, 37],  // UL
+    [1, 46],  // UB
+    [32, 16], // DR
+    [28, 25], // DF
+    [30, 43], // DL
+    [34, 52], // DB
+    [23, 12], // FR
+    [21, 41], // FL
+    [50, 39], // BL
+    [48, 14], // BR
+];
+
+#[rustfmt::skip]
+pub const CORNER_COLORS: [[Face; 3]; 8] = {
+    use Face::*;
+    [
+        [U, R, F], [U, F, L], [U, L, B], [U, B, R],
+        [D, F, R], [D, L, F], [D, B, L], [D, R, B],
+    ]
+};
+
+#[rustfmt::skip]
+pub const EDGE_COLORS: [[Face; 2]; 12] = {
+    use Face::*;
+    [
+        [U, R], [U, F], [U, L], [U, B],
+        [D, R], [D, F], [D, L], [D, B],
+        [F, R], [F, L], [B, L], [B, R],
+    ]
+};
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum FaceletError {
+    InvalidLength(usize),
+    InvalidColor(char),
+    CenterMismatch(Face),
+    ColorCount(Face, usize),
+    InvalidCorner(CornerCubePos),
+    InvalidEdge(EdgeCubePos),
+}
+
+impl fmt::Display for FaceletError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            FaceletError::InvalidLength(n) => write!(f, "expected 54 facelets, got {}", n),
+            FaceletError::InvalidColor(c) => write!(f, "invalid facelet color `{}`", c),
+            FaceletError::CenterMismatch(face) => {
+                write!(f, "center of face {:?} has another color", face)
+            }
+            FaceletError::ColorCount(face, n) => {
+                write!(f, "color {:?} appears {} times instead of 9", face, n)
+            }
+            FaceletError::InvalidCorner(pos) => {
+                write!(f, "no corner matches the colors at {:?}", pos)
+            }
+            FaceletError::InvalidEdge(pos) => write!(f, "no edge matches the colors at {:?}", pos),
+        }
+    }
+}
+
+impl std::error::Error for FaceletError {}
+
+impl From<CubieLevel> for FaceletCube {
+    fn from(src: CubieLevel) -> Self {
+        let mut res = [Face::U; 54];
+        for (i, face) in res.iter_mut().enumerate() {
+            *face = CENTERS[i / 9];
+        }
+
+        for (i, c) in src.0.iter().enumerate() {
+            for k in 0..3 {
+                res[CORNER_FACELETS[i][(k + c.o as usize) % 3]] = CORNER_COLORS[c.c as usize][k];
+            }
+        }
+        for (i, e) in src.1.iter().enumerate() {
+            for k in 0..2 {
+                res[EDGE_FACELETS[i][(k + e.o as usize) % 2]] = EDGE_COLORS[e.e as usize][k];
+            }
+        }
+
+        FaceletCube(res)
+    }
+}
+
+impl std::convert::TryFrom<FaceletCube> for CubieLevel {
+    type Error = FaceletError;
+
+    fn try_from(src: FaceletCube) -> Result<Self, Self::Error> {
+        let f = &src.0;
+
+        for (i, &face) in CENTERS.iter().enumerate() {
+            if f[i * 9 + 4] != face {
+                return Err(FaceletError::CenterMismatch(face));
+            }
+        }
+        for &face in CENTERS.iter() {
+            let n = f.iter().filter(|&&x| x == face).count();
+            if n != 9 {
+                return Err(FaceletError::ColorCount(face, n));
+            }
+        }
+
+        let mut res = SOLVED;
+
+        for (i, facelets) in CORNER_FACELETS.iter().enumerate() {
+            let pos = SOLVED.0[i].c;
+            let o = (0..3)
+                .find(|&o| f[facelets[o]] == Face::U || f[facelets[o]] == Face::D)
+                .ok_or(FaceletError::InvalidCorner(pos))?;
+            let (col1, col2) = (f[facelets[(o + 1) % 3]], f[facelets[(o + 2) % 3]]);
+            let j = CORNER_COLORS
+                .iter()
+                .position(|cols| cols[0] == f[facelets[o]] && cols[1] == col1 && cols[2] == col2)
+                .ok_or(FaceletError::InvalidCorner(pos))?;
+            res.0[i] = CornerCube {
+                c: SOLVED.0[j].c,
+                o: o as u8,
+            };
+        }
+
+        for (i, facelets) in EDGE_FACELETS.iter().enumerate() {
+            let pos = SOLVED.1[i].e;
+            let (col0, col1) = (f[facelets[0]], f[facelets[1]]);
+            res.1[i] = EDGE_COLORS
+                .iter()
+                .enumerate()
+                .find_map(|(j, cols)| match (cols[0], cols[1]) {
+                    (a, b) if a == col0 && b == col1 => Some(EdgeCube {
+                        e: SOLVED.1[j].e,
+                        o: 0,
+                    }),
+                    (a, b) if a == col1 && b == col0 => Some(EdgeCube {
+                        e: SOLVED.1[j].e,
+                        o: 1,
+                    }),
+                    _ => None,
+                })
+                .ok_or(FaceletError::InvalidEdge(pos))?;
+        }
+
+        Ok(res)
+    }
+}
+
+impl FromStr for FaceletCube {
+    type Err = FaceletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let n = s.chars().count();
+        if n != 54 {
+            return Err(FaceletError::InvalidLength(n));
+        }
+
+        let mut res = [Face::U; 54];
+        for (face, c) in res.iter_mut().zip(s.chars()) {
+            *face = match c {
+                'U' => Face::U,
+                'R' => Face::R,
+                'F' => Face::F,
+                'D' => Face::D,
+                'L' => Face::L,
+                'B' => Face::B,
+                _ => return Err(FaceletError::InvalidColor(c)),
+            };
+        }
+        Ok(FaceletCube(res))
+    }
+}
+
+impl fmt::Display for FaceletCube {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for face in self.0.iter() {
+            write!(f, "{:?}", face)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for FaceletCube {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "FaceletCube({})", self)
+    }
+}
+
+#[test]
+fn facelet() {
+    use crate::cube::Move::*;
+    use std::convert::TryFrom;
+
+    assert_eq!(
+        FaceletCube::from(SOLVED).to_string(),
+        "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB"
+    );
+    assert_eq!(
+        FaceletCube::from(SOLVED.apply(R1)).to_string(),
+        "UUFUUFUUFRRRRRRRRRFFDFFDFFDDDBDDBDDBLLLLLLLLLUBBUBBUBB"
+    );
+
+    let cube = CubieLevel::from_scramble(&[R1, D3, F2, L3, U1, B3, R2, D1, F3, L1, U2, B1]);
+    let facelets: FaceletCube = FaceletCube::from(cube).to_string().parse().unwrap();
+    assert_eq!(CubieLevel::try_from(facelets), Ok(cube));
+
+    let mut facelets = FaceletCube::from(cube);
+    facelets
+        .0
+        .swap(CORNER_FACELETS[0][1], CORNER_FACELETS[0][2]);
+    assert_eq!(
+        CubieLevel::try_from(facelets),
+        Err(FaceletError::InvalidCorner(CornerCubePos::URF))
+    );
+}
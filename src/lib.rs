@@ -1,14 +1,16 @@
 #![feature(test)]
+#[cfg(test)]
 extern crate bincode;
 
 extern crate serde;
-extern crate serde_bytes;
 #[macro_use]
 extern crate serde_derive;
 
 #[macro_use]
 extern crate lazy_static;
 
+extern crate rand;
+
 extern crate strum;
 #[macro_use]
 extern crate strum_macros;
@@ -16,14 +18,15 @@ extern crate strum_macros;
 #[macro_use]
 extern crate num_derive;
 
+pub mod algorithm;
 pub mod cube;
 mod display;
+pub mod facelet;
+pub mod notation;
+pub mod scramble;
 pub mod solve;
 pub use display::RubikCube;
 
-mod hash;
-mod tee;
-
 extern crate test;
 #[cfg(test)]
 mod bench;
@@ -1,43 +1,363 @@
-use cubers::cube;
-use cubers::solve;
+use cubers::cube::{CubieLevel, Move};
+use cubers::facelet::FaceletCube;
+use cubers::notation::{format_moves, parse_moves};
+use cubers::scramble::{random_state, RandomMoveScrambler};
+use cubers::solve::prune::{self, Progress, Stats};
+use cubers::solve::solver::{Phase1Solver, Phase2Solver, Solver, TwoPhaseSolver};
+use cubers::solve::table::{TableHeader, TableKind};
+use cubers::solve::transition::TransitionTables;
+use cubers::solve::{Error, SolveOptions};
+use std::convert::TryFrom;
+use std::io::{Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::time::Duration;
+
+const USAGE: &str = "usage: cubers [options] <command>
+
+commands:
+    solve \"<scramble>\"          solve the cube reached by a scramble
+    solve --facelets <54 chars> solve a cube given as URFDLB facelets
+    scramble                    print a random-state scramble
+    tables build                build the transition and pruning tables into the table directory
+    tables verify               check the tables in the table directory
+    tables stats                print the depth histogram of the pruning tables
+
+options:
+    --tables <dir>              table directory (default: .)
+    --max-length <n>            move budget of a solution (default: 22)
+    --timeout <ms>              give up after ms milliseconds, keeping the best solution so far
+    --threads <n>               number of threads (default: 1 to solve, all cores to build tables)
+    --notation <name>           output notation: singmaster (default) or numeric
+    --seed <n>                  seed of the scramble generator
+    --moves <n>                 scramble with n random moves instead of a random state";
+
+#[derive(Copy, Clone)]
+enum Notation {
+    Singmaster, // R U' F2
+    Numeric,    // R1 U3 F2
+}
+
+struct Options {
+    tables: PathBuf,
+    max_length: usize,
+    notation: Notation,
+    seed: Option<u64>,
+    moves: Option<usize>,
+    timeout: Option<Duration>,
+    threads: Option<usize>,
+}
+
+impl Options {
+    fn format(&self, moves: &[Move]) -> String {
+        match self.notation {
+            Notation::Singmaster => format_moves(moves),
+            Notation::Numeric => moves
+                .iter()
+                .map(|m| format!("{:?}", m))
+                .collect::<Vec<_>>()
+                .join(" "),
+        }
+    }
+
+    fn transitions_path(&self) -> PathBuf {
+        self.tables.join("transitions.db")
+    }
+
+    fn phase1_path(&self) -> PathBuf {
+        self.tables.join("phase1.db")
+    }
+
+    fn phase2_path(&self) -> PathBuf {
+        self.tables.join("phase2.db")
+    }
+}
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("cubers: {}\n\n{}", msg, USAGE);
+    process::exit(2)
+}
+
+fn fail<E: std::fmt::Display>(err: E) -> ! {
+    eprintln!("cubers: {}", err);
+    process::exit(1)
+}
 
 fn main() {
-    println!("Hello, world!");
-
-    use cubers::cube::Move::*;
-    let mut cl = cube::SOLVED;
-    let v: Vec<cube::Move> = vec![
-        D1, L2, U3, L2, D3, B2, L2, U1, F2, D1, L2, F2, U1, D2, U2, F2, U3, D1, L2, U3, L2, D1, U3,
-        D2, L2, R2, B2,
-    ];
-    let v: Vec<cube::Move> = v[..].to_vec();
-    println!("{:?}", v);
-
-    for m in v.iter() {
-        cl = *m * cl;
-    }
-
-    let c = cubers::RubikCube(cl);
-    println!("{:?}", c);
-
-    use solve::solver::Solver;
-    let p2 = std::fs::File::open("phase2.db")
-        .map(|file| solve::solver::Phase2Solver::new_from_cache(file).unwrap())
-        .unwrap_or_else(|_| {
-            let res = solve::solver::Phase2Solver::new();
-            let file = std::io::BufWriter::new(std::fs::File::create("phase2.db").unwrap());
-            let _ = bincode::serialize_into(file, &res);
+    let mut opts = Options {
+        tables: PathBuf::from("."),
+        max_length: 22,
+        notation: Notation::Singmaster,
+        seed: None,
+        moves: None,
+        timeout: None,
+        threads: None,
+    };
+    let mut facelets = None;
+    let mut args = vec![];
+
+    let mut it = std::env::args().skip(1);
+    while let Some(arg) = it.next() {
+        let mut value = |name: &str| {
+            it.next()
+                .unwrap_or_else(|| usage_error(&format!("{} needs a value", name)))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                return;
+            }
+            "--tables" => opts.tables = PathBuf::from(value("--tables")),
+            "--max-length" => {
+                opts.max_length = value("--max-length")
+                    .parse()
+                    .unwrap_or_else(|_| usage_error("--max-length must be a number"))
+            }
+            "--notation" => {
+                opts.notation = match value("--notation").as_str() {
+                    "singmaster" => Notation::Singmaster,
+                    "numeric" => Notation::Numeric,
+                    s => usage_error(&format!("unknown notation `{}`", s)),
+                }
+            }
+            "--seed" => {
+                opts.seed = Some(
+                    value("--seed")
+                        .parse()
+                        .unwrap_or_else(|_| usage_error("--seed must be a number")),
+                )
+            }
+            "--timeout" => {
+                let ms = value("--timeout")
+                    .parse()
+                    .unwrap_or_else(|_| usage_error("--timeout must be a number"));
+                opts.timeout = Some(Duration::from_millis(ms));
+            }
+            "--threads" => {
+                opts.threads = Some(
+                    value("--threads")
+                        .parse()
+                        .unwrap_or_else(|_| usage_error("--threads must be a number")),
+                )
+            }
+            "--moves" => {
+                opts.moves = Some(
+                    value("--moves")
+                        .parse()
+                        .unwrap_or_else(|_| usage_error("--moves must be a number")),
+                )
+            }
+            "--facelets" => facelets = Some(value("--facelets")),
+            s if s.starts_with("--") => usage_error(&format!("unknown option `{}`", s)),
+            _ => args.push(arg),
+        }
+    }
+
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+    match (args.as_slice(), facelets) {
+        (["solve"], Some(facelets)) => {
+            let facelets: FaceletCube = facelets.parse().unwrap_or_else(|e| fail(e));
+            let cube = CubieLevel::try_from(facelets).unwrap_or_else(|e| fail(e));
+            solve(&opts, cube);
+        }
+        (["solve", scramble], None) => {
+            let moves = parse_moves(scramble).unwrap_or_else(|e| fail(e));
+            solve(&opts, CubieLevel::from_scramble(&moves));
+        }
+        (["scramble"], None) => scramble(&opts),
+        (["tables", "build"], None) => build_tables(&opts),
+        (["tables", "verify"], None) => verify_tables(&opts),
+        (["tables", "stats"], None) => table_stats(&opts),
+        ([], _) => usage_error("no command given"),
+        _ => usage_error("invalid command"),
+    }
+}
+
+fn solver(opts: &Options) -> TwoPhaseSolver {
+    load_or_build(
+        &opts.transitions_path(),
+        |file| TransitionTables::load(std::io::BufReader::new(file)),
+        TransitionTables::build,
+        TransitionTables::save,
+    )
+    .install();
+    let phase1 = load_or_build(
+        &opts.phase1_path(),
+        Phase1Solver::new_from_mmap,
+        || Phase1Solver::build(prune::default_threads(), &mut |_| ()),
+        Phase1Solver::write_cache,
+    );
+    let phase2 = load_or_build(
+        &opts.phase2_path(),
+        Phase2Solver::new_from_mmap,
+        || Phase2Solver::build(prune::default_threads(), &mut |_| ()),
+        Phase2Solver::write_cache,
+    );
+    TwoPhaseSolver::new(phase1, phase2, opts.max_length).with_threads(opts.threads.unwrap_or(1))
+}
+
+fn solve(opts: &Options, cube: CubieLevel) {
+    let solver = solver(opts);
+    let limits = opts
+        .timeout
+        .map_or_else(SolveOptions::default, SolveOptions::with_timeout);
+    match solver.solve_with(cube, &limits) {
+        Ok(moves) => println!("{}", opts.format(&moves)),
+        Err(e) => fail(e),
+    }
+}
+
+fn scramble(opts: &Options) {
+    use rand::{Rng, SeedableRng};
+
+    let seed = opts.seed.unwrap_or_else(|| rand::thread_rng().gen());
+    if let Some(n) = opts.moves {
+        let moves = RandomMoveScrambler::<Move>::new(seed).scramble(n);
+        println!("{}", opts.format(&moves));
+        return;
+    }
+
+    let mut rng = rand::rngs::StdRng::seed_from_u64(seed);
+    match random_state(&solver(opts), &mut rng) {
+        Ok(moves) => println!("{}", opts.format(&moves)),
+        Err(e) => fail(e),
+    }
+}
+
+fn load_or_build<T, L, B, S>(path: &Path, load: L, build: B, save: S) -> T
+where
+    L: FnOnce(&std::fs::File) -> Result<T, Error>,
+    B: FnOnce() -> T,
+    S: FnOnce(&T, std::io::BufWriter<std::fs::File>) -> Result<(), Error>,
+{
+    match std::fs::File::open(path) {
+        Ok(file) => load(&file).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e))),
+        Err(_) => {
+            eprintln!("cubers: building {}", path.display());
+            let res = build();
+            write(path, |file| save(&res, file));
             res
-        });
+        }
+    }
+}
+
+// written next to path and renamed over it, so processes mapping the old file are unaffected
+fn write<S>(path: &Path, save: S)
+where
+    S: FnOnce(std::io::BufWriter<std::fs::File>) -> Result<(), Error>,
+{
+    let tmp = path.with_extension("db.tmp");
+    let file =
+        std::fs::File::create(&tmp).unwrap_or_else(|e| fail(format!("{}: {}", tmp.display(), e)));
+    save(std::io::BufWriter::new(file))
+        .unwrap_or_else(|e| fail(format!("{}: {}", tmp.display(), e)));
+    std::fs::rename(&tmp, path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+}
+
+fn build_tables(opts: &Options) {
+    std::fs::create_dir_all(&opts.tables)
+        .unwrap_or_else(|e| fail(format!("{}: {}", opts.tables.display(), e)));
+
+    eprintln!("cubers: building {}", opts.transitions_path().display());
+    let transitions = TransitionTables::build();
+    write(&opts.transitions_path(), |file| transitions.save(file));
+    transitions.install();
+    let threads = opts.threads.unwrap_or_else(prune::default_threads);
+    let path = opts.phase1_path();
+    eprintln!("cubers: building {}", path.display());
+    let phase1 = Phase1Solver::build(threads, &mut |p| report(&path, p));
+    write(&path, |file| phase1.write_cache(file));
+    let path = opts.phase2_path();
+    eprintln!("cubers: building {}", path.display());
+    let phase2 = Phase2Solver::build(threads, &mut |p| report(&path, p));
+    write(&path, |file| phase2.write_cache(file));
+}
 
-    let solve = p2.solve(c.0);
-    println!("{:?}", solve);
+fn report(path: &Path, p: &Progress) {
+    eprintln!(
+        "cubers: {}: depth {:2}: {:9} entries, {:6.2}% filled{}",
+        path.display(),
+        p.depth,
+        p.count,
+        p.filled as f64 * 100.0 / p.total as f64,
+        if p.backward { " (backward)" } else { "" }
+    );
+}
 
-    if let Ok(moves) = solve {
-        for m in moves {
-            cl = m * cl;
+fn verify_tables(opts: &Options) {
+    fn check<T>(path: &Path, load: fn(std::fs::File) -> Result<T, Error>) -> bool {
+        let res = std::fs::File::open(path)
+            .map_err(Error::from)
+            .and_then(|mut file| {
+                let header = TableHeader::read_from(&mut file)?;
+                file.seek(SeekFrom::Start(0))?;
+                load(file).map(|_| header)
+            });
+        match res {
+            Ok(ref h) if h.kind == TableKind::Transition => println!(
+                "{}: ok ({} tables, format version {}, {} tables, {} entries)",
+                path.display(),
+                h.kind,
+                h.version,
+                h.sizes[0],
+                h.sizes[1]
+            ),
+            Ok(ref h) => println!(
+                "{}: ok ({} table, format version {}, {}x{} entries)",
+                path.display(),
+                h.kind,
+                h.version,
+                h.sizes[0],
+                h.sizes[1]
+            ),
+            Err(ref e) => println!("{}: {}", path.display(), e),
         }
-        let c = cubers::RubikCube(cl);
-        println!("{:?}", c);
+        res.is_ok()
+    }
+
+    // beyond the checksums, every pruning table entry must lead down to the goal
+    let ok0 = check(&opts.transitions_path(), |file| {
+        TransitionTables::load(file).map(TransitionTables::install)
+    });
+    let ok1 = check(&opts.phase1_path(), |file| {
+        Phase1Solver::new_from_cache(file)?.verify()
+    });
+    let ok2 = check(&opts.phase2_path(), |file| {
+        Phase2Solver::new_from_cache(file)?.verify()
+    });
+    if !(ok0 && ok1 && ok2) {
+        process::exit(1);
     }
 }
+
+fn table_stats(opts: &Options) {
+    fn print(path: &Path, stats: Stats) {
+        println!(
+            "{}: {:.2}% filled, average depth {:.3}",
+            path.display(),
+            stats.fill * 100.0,
+            stats.average
+        );
+        for (depth, count) in stats.depths.iter().enumerate() {
+            println!("    depth {:2}: {:9}", depth, count);
+        }
+    }
+
+    let open = |path: &Path| {
+        std::fs::File::open(path).unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)))
+    };
+    if let Ok(file) = std::fs::File::open(opts.transitions_path()) {
+        if let Ok(tables) = TransitionTables::load(std::io::BufReader::new(file)) {
+            tables.install();
+        }
+    }
+
+    let path = opts.phase1_path();
+    let phase1 = Phase1Solver::new_from_mmap(&open(&path))
+        .unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    print(&path, phase1.stats());
+    let path = opts.phase2_path();
+    let phase2 = Phase2Solver::new_from_mmap(&open(&path))
+        .unwrap_or_else(|e| fail(format!("{}: {}", path.display(), e)));
+    print(&path, phase2.stats());
+}
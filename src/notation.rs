@@ -0,0 +1,110 @@
+use crate::cube::Move;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseMoveError {
+    pub position: usize, // byte offset of the token in the input
+    pub token: String,
+}
+
+impl fmt::Display for ParseMoveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "invalid move `{}` at position {}",
+            self.token, self.position
+        )
+    }
+}
+
+impl std::error::Error for ParseMoveError {}
+
+impl FromStr for Move {
+    type Err = ParseMoveError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        use Move::*;
+
+        let err = || ParseMoveError {
+            position: 0,
+            token: s.to_string(),
+        };
+
+        let mut chars = s.chars();
+        let face = match chars.next() {
+            Some('U') => [U1, U2, U3],
+            Some('D') => [D1, D2, D3],
+            Some('F') => [F1, F2, F3],
+            Some('B') => [B1, B2, B3],
+            Some('L') => [L1, L2, L3],
+            Some('R') => [R1, R2, R3],
+            _ => return Err(err()),
+        };
+        match chars.as_str() {
+            "" => Ok(face[0]),
+            "2" | "2'" => Ok(face[1]),
+            "'" => Ok(face[2]),
+            _ => Err(err()),
+        }
+    }
+}
+
+impl fmt::Display for Move {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        const FACES: [char; 6] = ['U', 'D', 'F', 'B', 'L', 'R'];
+        const SUFFIXES: [&str; 3] = ["", "2", "'"];
+
+        write!(f, "{}{}", FACES[self.face()], SUFFIXES[*self as usize % 3])
+    }
+}
+
+pub fn parse_moves(s: &str) -> Result<Vec<Move>, ParseMoveError> {
+    let mut res = vec![];
+
+    let mut rest = s;
+    while let Some(start) = rest.find(|c: char| !c.is_whitespace()) {
+        let token = &rest[start..];
+        let end = token.find(char::is_whitespace).unwrap_or(token.len());
+        let position = s.len() - token.len();
+
+        let m = token[..end]
+            .parse::<Move>()
+            .map_err(|e| ParseMoveError { position, ..e })?;
+        res.push(m);
+
+        rest = &token[end..];
+    }
+
+    Ok(res)
+}
+
+pub fn format_moves(moves: &[Move]) -> String {
+    moves
+        .iter()
+        .map(|m| m.to_string())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn notation() {
+    use strum::IntoEnumIterator;
+
+    for m in Move::iter() {
+        assert_eq!(m.to_string().parse::<Move>(), Ok(m));
+    }
+
+    use Move::*;
+    let moves = parse_moves("  R U R' U2 F2  B2' ").unwrap();
+    assert_eq!(moves, vec![R1, U1, R3, U2, F2, B2]);
+    assert_eq!(format_moves(&moves), "R U R' U2 F2 B2");
+
+    assert_eq!(
+        parse_moves("R U x2 F"),
+        Err(ParseMoveError {
+            position: 4,
+            token: "x2".to_string(),
+        })
+    );
+}
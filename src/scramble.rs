@@ -0,0 +1,134 @@
+use crate::algorithm::Algorithm;
+use crate::cube::{CubieLevel, Move, VerifyError, SOLVED};
+use crate::solve::solver::{Solver, StaticExactSizeIterator};
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+
+// uniformly random among the reachable states
+pub fn random_cube<R: Rng + ?Sized>(rng: &mut R) -> CubieLevel {
+    let mut res = SOLVED;
+    res.0.shuffle(rng);
+    res.1.shuffle(rng);
+
+    // the last orientation is determined by the others
+    let mut twist = 0;
+    for c in res.0[1..].iter_mut() {
+        c.o = rng.gen_range(0, 3);
+        twist += c.o;
+    }
+    res.0[0].o = (3 - twist % 3) % 3;
+
+    let mut flip = 0;
+    for e in res.1[1..].iter_mut() {
+        e.o = rng.gen_range(0, 2);
+        flip += e.o;
+    }
+    res.1[0].o = flip % 2;
+
+    // swapping two edges maps odd permutations onto even ones one-to-one
+    if res.verify() == Err(VerifyError::ParityMismatch) {
+        res.1.swap(0, 1);
+    }
+    debug_assert_eq!(res.verify(), Ok(()));
+
+    res
+}
+
+// a scramble reaching a random state: the inverse of its solution
+pub fn random_state<S, R>(solver: &S, rng: &mut R) -> Result<Algorithm, S::Error>
+where
+    S: Solver,
+    R: Rng + ?Sized,
+{
+    let cube = random_cube(rng);
+    let solution = Algorithm(solver.solve(cube)?);
+    Ok(solution.inverse())
+}
+
+// random moves from M (Move, or P2Move to stay in G1)
+pub struct RandomMoveScrambler<M> {
+    moves: Vec<Move>,
+    rng: StdRng,
+    _marker: std::marker::PhantomData<M>,
+}
+
+impl<M: StaticExactSizeIterator + Into<Move>> RandomMoveScrambler<M> {
+    pub fn new(seed: u64) -> Self {
+        RandomMoveScrambler {
+            moves: M::iter().map(Into::into).collect(),
+            rng: StdRng::seed_from_u64(seed),
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    // no face twice in a row, and no `U D U`
+    pub fn scramble(&mut self, len: usize) -> Algorithm {
+        let mut res: Vec<Move> = Vec::with_capacity(len);
+        while res.len() < len {
+            let m = *self.moves.choose(&mut self.rng).unwrap();
+            let redundant = match *res.as_slice() {
+                [.., b, a] => {
+                    m.face() == a.face() || (m.axis() == a.axis() && m.axis() == b.axis())
+                }
+                [a] => m.face() == a.face(),
+                [] => false,
+            };
+            if !redundant {
+                res.push(m);
+            }
+        }
+        Algorithm(res)
+    }
+}
+
+#[test]
+fn scramble() {
+    use crate::solve::solver::{test_tables, TwoPhaseSolver};
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let cubes: Vec<_> = (0..100).map(|_| random_cube(&mut rng)).collect();
+    for cube in cubes.iter() {
+        assert_eq!(cube.verify(), Ok(()));
+    }
+
+    let mut rng = StdRng::seed_from_u64(42);
+    assert_eq!(random_cube(&mut rng), cubes[0]);
+
+    let (p1, p2) = test_tables();
+    let solver = TwoPhaseSolver::new(p1.clone(), p2.clone(), 22);
+
+    let mut rng = StdRng::seed_from_u64(42);
+    let scramble = random_state(&solver, &mut rng).unwrap();
+    assert_eq!(scramble.apply(&SOLVED), cubes[0]);
+
+    // seeds 1 and 5 used to give `B2 B'` and `L2 L'` where the two phases meet
+    for seed in 0..10 {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let scramble = random_state(&solver, &mut rng).unwrap();
+        for w in scramble.windows(2) {
+            assert_ne!(w[0].face(), w[1].face(), "seed {}: {:?}", seed, scramble);
+        }
+    }
+}
+
+#[test]
+fn random_move() {
+    use crate::solve::phase2::p2move::P2Move;
+    use crate::solve::phase2::Phase2Cube;
+    use std::convert::TryFrom;
+
+    let scramble = RandomMoveScrambler::<Move>::new(1).scramble(100);
+    assert_eq!(scramble.len(), 100);
+    assert_eq!(scramble, RandomMoveScrambler::<Move>::new(1).scramble(100));
+    assert_ne!(scramble, RandomMoveScrambler::<Move>::new(2).scramble(100));
+    for w in scramble.windows(2) {
+        assert_ne!(w[0].face(), w[1].face());
+    }
+    for w in scramble.windows(3) {
+        assert!(w[0].axis() != w[1].axis() || w[1].axis() != w[2].axis());
+    }
+
+    let scramble = RandomMoveScrambler::<P2Move>::new(1).scramble(100);
+    assert!(Phase2Cube::try_from(CubieLevel::from_scramble(&scramble)).is_ok());
+}
@@ -1,8 +1,14 @@
 use super::cube;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 pub mod phase1;
 pub mod phase2;
+pub mod prune;
 pub mod solver;
+pub mod table;
+pub mod transition;
 pub mod util;
 
 pub trait Phase {
@@ -10,3 +16,166 @@ pub trait Phase {
 
     fn solve(&self, cube: &crate::RubikCube) -> Result<Vec<cube::Move>, Self::Error>;
 }
+
+#[derive(Debug, Clone, Default)]
+pub struct SolveOptions {
+    pub deadline: Option<Instant>,
+    pub max_nodes: Option<u64>,
+    pub cancel: Option<CancelToken>,
+}
+
+impl SolveOptions {
+    pub fn with_timeout(timeout: Duration) -> Self {
+        SolveOptions {
+            deadline: Some(Instant::now() + timeout),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+// nodes visited by one search, checked against its options.
+// the threads of a parallel search share one, so max_nodes bounds them all together
+pub struct Budget<'a> {
+    opts: &'a SolveOptions,
+    nodes: AtomicU64,
+    stop: Option<&'a AtomicBool>,
+}
+
+impl<'a> Budget<'a> {
+    pub fn new(opts: &'a SolveOptions) -> Self {
+        Budget {
+            opts,
+            nodes: AtomicU64::new(0),
+            stop: None,
+        }
+    }
+
+    // a search split over threads, which give up once stop is set
+    pub fn with_stop(opts: &'a SolveOptions, stop: &'a AtomicBool) -> Self {
+        Budget {
+            stop: Some(stop),
+            ..Budget::new(opts)
+        }
+    }
+
+    pub fn nodes(&self) -> u64 {
+        self.nodes.load(Ordering::Relaxed)
+    }
+
+    pub fn tick(&self) -> Result<(), Error> {
+        let nodes = self.nodes.fetch_add(1, Ordering::Relaxed) + 1;
+
+        if self.opts.max_nodes.is_some_and(|max| nodes > max) {
+            return Err(Error::BudgetExceeded);
+        }
+        // the clock and the token are only polled now and then
+        if nodes & 1023 == 0 {
+            if self.opts.cancel.as_ref().is_some_and(|c| c.is_cancelled())
+                || self.stop.is_some_and(|s| s.load(Ordering::Relaxed))
+            {
+                return Err(Error::Cancelled);
+            }
+            if self.opts.deadline.is_some_and(|d| Instant::now() >= d) {
+                return Err(Error::BudgetExceeded);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum Error {
+    NotInSubgroup,
+    InvalidCube(cube::VerifyError),
+    TableCorrupt,
+    TableIo(std::io::Error),
+    TableMagic,
+    TableVersion(u32),
+    TableKind(table::TableKind),
+    TableSize([u64; 2]),
+    TableModulus(u32),
+    TableChecksum(u32),
+    TableInconsistent(usize),
+    BudgetExceeded,
+    Cancelled,
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            Error::NotInSubgroup => write!(f, "cube is not in the subgroup of this phase"),
+            Error::InvalidCube(e) => write!(f, "invalid cube: {}", e),
+            Error::TableCorrupt => write!(f, "pruning table is corrupt"),
+            Error::TableIo(e) => write!(f, "failed to read pruning table: {}", e),
+            Error::TableMagic => write!(f, "not a pruning table file"),
+            Error::TableVersion(v) => write!(
+                f,
+                "pruning table has format version {}, expected {}",
+                v,
+                table::VERSION
+            ),
+            Error::TableKind(k) => write!(f, "pruning table is a {} table", k),
+            Error::TableSize([a, b]) => {
+                write!(
+                    f,
+                    "pruning table has unexpected coordinate sizes {}x{}",
+                    a, b
+                )
+            }
+            Error::TableModulus(m) => write!(f, "pruning table stores depths modulo {}, not 3", m),
+            Error::TableInconsistent(i) => write!(
+                f,
+                "pruning table entry {} has no neighbour one step closer",
+                i
+            ),
+            Error::TableChecksum(c) => {
+                write!(
+                    f,
+                    "pruning table checksum mismatch (payload crc32 {:08x})",
+                    c
+                )
+            }
+            Error::BudgetExceeded => write!(f, "no solution within the budget"),
+            Error::Cancelled => write!(f, "search was cancelled"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::InvalidCube(e) => Some(e),
+            Error::TableIo(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<cube::VerifyError> for Error {
+    fn from(src: cube::VerifyError) -> Self {
+        Error::InvalidCube(src)
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(src: std::io::Error) -> Self {
+        Error::TableIo(src)
+    }
+}
@@ -0,0 +1,160 @@
+use super::etwist::{ETwist, COUNT as ETWIST_COUNT};
+use super::udslice::{UDSlice, COUNT as UDSLICE_COUNT};
+use crate::cube;
+use crate::solve::util::Memo;
+use cube::Sym16;
+
+// edge flip and UD slice together; unlike the flip alone, it is closed under Sym16
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FlipSlice(pub u32);
+pub const COUNT: usize = UDSLICE_COUNT * ETWIST_COUNT;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct FlipSliceClass(pub u16);
+pub const CLASS_COUNT: usize = 64430;
+
+impl FlipSlice {
+    pub fn new(uds: UDSlice, etwi: ETwist) -> Self {
+        FlipSlice(uds.0 as u32 * ETWIST_COUNT as u32 + etwi.0 as u32)
+    }
+
+    pub fn uds(self) -> UDSlice {
+        UDSlice((self.0 / ETWIST_COUNT as u32) as u16)
+    }
+
+    pub fn etwi(self) -> ETwist {
+        ETwist((self.0 % ETWIST_COUNT as u32) as u16)
+    }
+
+    fn iter() -> impl Iterator<Item = FlipSlice> {
+        (0..COUNT as u32).map(FlipSlice)
+    }
+}
+
+impl From<cube::CubieLevel> for FlipSlice {
+    fn from(cl: cube::CubieLevel) -> Self {
+        FlipSlice::new(cl.into(), cl.into())
+    }
+}
+impl From<FlipSlice> for cube::CubieLevel {
+    // return a representation
+    fn from(fs: FlipSlice) -> Self {
+        let mut res = cube::CubieLevel::from(fs.uds());
+        let flip = cube::CubieLevel::from(fs.etwi());
+        for (e, f) in res.1.iter_mut().zip(flip.1.iter()) {
+            e.o = f.o;
+        }
+        res
+    }
+}
+
+pub(crate) static CLASS_MEMO: Memo = Memo::new(COUNT, || {
+    let mut memo = vec![!0; COUNT];
+    let mut cnt = 0;
+
+    for fs in FlipSlice::iter() {
+        let cube: cube::CubieLevel = fs.into();
+        memo[fs.0 as usize] = Sym16::iter()
+            .find_map(|s| {
+                let v: FlipSlice = (s * cube).into();
+                if memo[v.0 as usize] != !0 {
+                    return Some(memo[v.0 as usize]);
+                }
+                None
+            })
+            .unwrap_or_else(|| {
+                cnt += 1;
+                cnt - 1
+            });
+    }
+    assert_eq!(cnt as usize, CLASS_COUNT);
+
+    memo
+});
+impl From<FlipSlice> for FlipSliceClass {
+    fn from(src: FlipSlice) -> FlipSliceClass {
+        FlipSliceClass(CLASS_MEMO.get()[src.0 as usize])
+    }
+}
+
+// the first FlipSlice of every class, as slice and flip
+pub(crate) static REPR_MEMO: Memo = Memo::new(CLASS_COUNT * 2, || {
+    let mut memo = vec![!0; CLASS_COUNT * 2];
+
+    for fs in FlipSlice::iter() {
+        let idx = FlipSliceClass::from(fs).0 as usize;
+        if memo[idx * 2] == !0 {
+            memo[idx * 2] = fs.uds().0;
+            memo[idx * 2 + 1] = fs.etwi().0;
+        }
+    }
+    memo
+});
+impl From<FlipSliceClass> for FlipSlice {
+    // return a representation
+    fn from(src: FlipSliceClass) -> FlipSlice {
+        let memo = REPR_MEMO.get();
+        let idx = src.0 as usize * 2;
+        FlipSlice::new(UDSlice(memo[idx]), ETwist(memo[idx + 1]))
+    }
+}
+
+pub(crate) static SYM16_MEMO: Memo = Memo::new(COUNT, || {
+    let mut memo = vec![!0; COUNT];
+    for fs in FlipSlice::iter() {
+        let repr = FlipSlice::from(FlipSliceClass::from(fs));
+        let cube: cube::CubieLevel = fs.into();
+        memo[fs.0 as usize] = Sym16::iter()
+            .find(|&s| repr == (s * cube).into())
+            .unwrap()
+            .0 as u16;
+    }
+    memo
+});
+impl From<FlipSlice> for Sym16 {
+    // the symmetry taking fs to the representation of its class
+    fn from(src: FlipSlice) -> Self {
+        Sym16(SYM16_MEMO.get()[src.0 as usize] as u8)
+    }
+}
+
+// the symmetries fixing the representation of each class, one bit each
+pub(crate) static STAB_MEMO: Memo = Memo::new(CLASS_COUNT, || {
+    let mut memo = vec![0; CLASS_COUNT];
+    for (i, v) in memo.iter_mut().enumerate() {
+        let repr = FlipSlice::from(FlipSliceClass(i as u16));
+        let cube: cube::CubieLevel = repr.into();
+        for s in Sym16::iter() {
+            if repr == (s * cube).into() {
+                *v |= 1 << s.0;
+            }
+        }
+    }
+    memo
+});
+impl FlipSliceClass {
+    pub fn stabilizer(self) -> impl Iterator<Item = Sym16> {
+        let bits = STAB_MEMO.get()[self.0 as usize];
+        Sym16::iter().filter(move |s| bits & (1 << s.0) != 0)
+    }
+}
+
+#[test]
+fn flipslice() {
+    use crate::cube::Move;
+    use strum::IntoEnumIterator;
+
+    for m in Move::iter() {
+        let cube = m * (Move::F1 * (Move::R3 * cube::SOLVED));
+        let fs = FlipSlice::from(cube);
+        assert_eq!(FlipSlice::from(cube::CubieLevel::from(fs)), fs);
+
+        let class = FlipSliceClass::from(fs);
+        let repr = FlipSlice::from(class);
+        assert_eq!(FlipSlice::from(Sym16::from(fs) * cube), repr);
+        for s in Sym16::iter() {
+            assert_eq!(FlipSliceClass::from(FlipSlice::from(s * cube)), class);
+        }
+        assert!(class.stabilizer().any(|s| s == Sym16(0)));
+    }
+}
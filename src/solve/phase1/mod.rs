@@ -1,3 +1,238 @@
-mod ctwist;
-mod etwist;
-mod udslice;
+pub(crate) mod ctwist;
+pub(crate) mod etwist;
+pub(crate) mod flipslice;
+pub(crate) mod udslice;
+
+use self::ctwist::CTwist;
+use self::etwist::ETwist;
+use self::flipslice::{FlipSlice, FlipSliceClass};
+use self::udslice::UDSlice;
+
+pub use self::ctwist::COUNT as CTWIST_COUNT;
+pub use self::etwist::COUNT as ETWIST_COUNT;
+pub use self::flipslice::CLASS_COUNT as FLIPSLICE_CLASS_COUNT;
+pub use self::udslice::COUNT as UDSLICE_COUNT;
+
+use crate::cube;
+use cube::{Move, Sym1, Sym16};
+
+use std::ops::Mul;
+
+pub const MAX_STEPS: usize = 12;
+
+#[derive(Clone)]
+pub struct Phase1Cube(cube::CubieLevel);
+
+impl std::convert::TryFrom<cube::CubieLevel> for Phase1Cube {
+    type Error = crate::solve::Error;
+    fn try_from(src: cube::CubieLevel) -> Result<Self, Self::Error> {
+        src.verify()?;
+        Ok(Phase1Cube(src))
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct Phase1Vec {
+    ctwi: CTwist,
+    etwi: ETwist,
+    uds: UDSlice,
+}
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Phase1Coord(u32, u16);
+
+impl From<Phase1Coord> for Phase1Vec {
+    fn from(src: Phase1Coord) -> Self {
+        let (ctwi, uds) = (src.0 / UDSLICE_COUNT as u32, src.0 % UDSLICE_COUNT as u32);
+        Phase1Vec {
+            ctwi: CTwist(ctwi as u16),
+            etwi: ETwist(src.1),
+            uds: UDSlice(uds as u16),
+        }
+    }
+}
+impl From<Phase1Vec> for Phase1Coord {
+    fn from(src: Phase1Vec) -> Self {
+        Phase1Coord(
+            src.ctwi.0 as u32 * UDSLICE_COUNT as u32 + src.uds.0 as u32,
+            src.etwi.0,
+        )
+    }
+}
+
+impl<T: Into<Phase1Cube>> From<T> for Phase1Coord {
+    fn from(src: T) -> Self {
+        let src: Phase1Cube = src.into();
+        let (v1, v2, v3): (CTwist, ETwist, UDSlice) =
+            ((src).0.into(), (src).0.into(), (src).0.into());
+        Phase1Vec {
+            ctwi: v1,
+            etwi: v2,
+            uds: v3,
+        }
+        .into()
+    }
+}
+
+impl Mul<Phase1Vec> for Move {
+    type Output = Phase1Vec;
+    fn mul(self, rhs: Phase1Vec) -> Self::Output {
+        Phase1Vec {
+            ctwi: self * rhs.ctwi,
+            etwi: self * rhs.etwi,
+            uds: self * rhs.uds,
+        }
+    }
+}
+impl Mul<Phase1Coord> for Move {
+    type Output = Phase1Coord;
+    fn mul(self, rhs: Phase1Coord) -> Self::Output {
+        let v: Phase1Vec = rhs.into();
+        (self * v).into()
+    }
+}
+
+impl Mul<Phase1Coord> for Sym1 {
+    type Output = Phase1Coord;
+    fn mul(self, rhs: Phase1Coord) -> Self::Output {
+        rhs
+    }
+}
+
+// the flip and slice reduced by Sym16, with the twist conjugated along
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct PruneVec {
+    class: FlipSliceClass,
+    ctwi: CTwist,
+}
+
+impl PruneVec {
+    // the entries of the same cubes, when the representation of the class is symmetric
+    pub fn equivalents(self) -> impl Iterator<Item = PruneVec> {
+        self.class.stabilizer().map(move |s| PruneVec {
+            class: self.class,
+            ctwi: s * self.ctwi,
+        })
+    }
+}
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct PruneCoord(u32);
+
+impl From<PruneCoord> for usize {
+    fn from(src: PruneCoord) -> usize {
+        src.0 as usize
+    }
+}
+impl From<usize> for PruneCoord {
+    fn from(src: usize) -> Self {
+        PruneCoord(src as u32)
+    }
+}
+
+impl From<PruneVec> for PruneCoord {
+    fn from(src: PruneVec) -> Self {
+        PruneCoord((src.class.0 as usize * CTWIST_COUNT + src.ctwi.0 as usize) as u32)
+    }
+}
+impl From<PruneCoord> for PruneVec {
+    fn from(src: PruneCoord) -> Self {
+        let (class, ctwi) = (src.0 as usize / CTWIST_COUNT, src.0 as usize % CTWIST_COUNT);
+        PruneVec {
+            class: FlipSliceClass(class as u16),
+            ctwi: CTwist(ctwi as u16),
+        }
+    }
+}
+
+impl From<Phase1Vec> for PruneVec {
+    fn from(src: Phase1Vec) -> Self {
+        let fs = FlipSlice::new(src.uds, src.etwi);
+        let s = Sym16::from(fs);
+
+        PruneVec {
+            class: fs.into(),
+            ctwi: s * src.ctwi,
+        }
+    }
+}
+impl From<Phase1Coord> for PruneVec {
+    fn from(src: Phase1Coord) -> Self {
+        Phase1Vec::from(src).into()
+    }
+}
+impl From<Phase1Vec> for PruneCoord {
+    fn from(src: Phase1Vec) -> Self {
+        PruneVec::from(src).into()
+    }
+}
+
+impl From<PruneVec> for Phase1Vec {
+    // representation
+    fn from(src: PruneVec) -> Self {
+        let fs = FlipSlice::from(src.class);
+        Phase1Vec {
+            ctwi: src.ctwi,
+            etwi: fs.etwi(),
+            uds: fs.uds(),
+        }
+    }
+}
+
+impl From<PruneCoord> for Phase1Coord {
+    // representation
+    fn from(src: PruneCoord) -> Self {
+        Phase1Vec::from(PruneVec::from(src)).into()
+    }
+}
+
+impl From<Phase1Coord> for PruneCoord {
+    fn from(src: Phase1Coord) -> Self {
+        Phase1Vec::from(src).into()
+    }
+}
+
+#[test]
+fn rotate_test() {
+    use std::convert::TryInto;
+    use strum::IntoEnumIterator;
+
+    let solved: Phase1Cube = cube::SOLVED.try_into().unwrap();
+    let solved: Phase1Coord = solved.into();
+
+    for m in Move::iter() {
+        let cube = m * cube::SOLVED;
+        let cube: Phase1Cube = cube.try_into().unwrap();
+        let v1: Phase1Coord = cube.into();
+
+        let v2 = m * solved;
+        assert_eq!(v1, v2, "move {:?}", m);
+    }
+
+    use Move::*;
+    let cube = F1 * (U1 * (L3 * cube::SOLVED));
+    let cube: Phase1Cube = cube.try_into().unwrap();
+    let cube: Phase1Coord = cube.into();
+    let cube = L1 * (U3 * (F3 * cube));
+    assert_eq!(cube, solved);
+}
+
+#[test]
+fn prune_sym() {
+    use std::convert::TryInto;
+    use Move::*;
+
+    for cube in [
+        cube::CubieLevel::from_scramble(&[R1, U1, F3, L2, D1, B1, R3]),
+        cube::CubieLevel::from_scramble(&[F1, L3, U2, B1, D3, R2, F3, U1, L1]),
+    ] {
+        let pc = |cube: cube::CubieLevel| {
+            let cube: Phase1Cube = cube.try_into().unwrap();
+            PruneCoord::from(Phase1Coord::from(cube))
+        };
+        // conjugates are as far from G1, so they share an entry
+        for s in Sym16::iter() {
+            assert_eq!(pc(s * cube), pc(cube), "{:?}", s);
+        }
+        let v = PruneVec::from(pc(cube));
+        assert_eq!(PruneVec::from(Phase1Vec::from(v)), v);
+    }
+}
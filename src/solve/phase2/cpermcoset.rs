@@ -1,6 +1,7 @@
 use super::cperm::{CPerm, COUNT as CPERM_COUNT};
 use super::p2move::{P2Move, P2MOVE_COUNT};
 use crate::cube;
+use crate::solve::util::Memo;
 use cube::Sym16;
 use std::ops::Mul;
 use strum::IntoEnumIterator;
@@ -12,78 +13,73 @@ pub struct CPermCoset(pub u16);
 pub struct Iter(u16);
 pub const CPERMCOSET_COUNT: usize = 2768;
 
-impl From<CPerm> for CPermCoset {
-    fn from(src: CPerm) -> CPermCoset {
-        lazy_static! {
-            static ref MEMO: Vec<CPermCoset> = {
-                let mut memo = vec![CPermCoset(!0); CPERM_COUNT];
-                let mut cnt = 0;
+pub(crate) static COSET_MEMO: Memo = Memo::new(CPERM_COUNT, || {
+    let mut memo = vec![!0; CPERM_COUNT];
+    let mut cnt = 0;
 
-                for (i, cp) in CPerm::iter().enumerate() {
-                    let cube: cube::CubieLevel = cp.into();
-                    memo[i] = Sym16::iter()
-                        .find_map(|s| {
-                            let cube = s * cube;
-                            let v: CPerm = cube.into();
+    for (i, cp) in CPerm::iter().enumerate() {
+        let cube: cube::CubieLevel = cp.into();
+        memo[i] = Sym16::iter()
+            .find_map(|s| {
+                let cube = s * cube;
+                let v: CPerm = cube.into();
 
-                            if memo[v.0 as usize] != CPermCoset(!0) {
-                                return Some(memo[v.0 as usize]);
-                            }
-                            None
-                        })
-                        .unwrap_or_else(|| {
-                            cnt += 1;
-                            CPermCoset((cnt - 1) as u16)
-                        });
+                if memo[v.0 as usize] != !0 {
+                    return Some(memo[v.0 as usize]);
                 }
-                assert_eq!(cnt, CPERMCOSET_COUNT);
+                None
+            })
+            .unwrap_or_else(|| {
+                cnt += 1;
+                cnt - 1
+            });
+    }
+    assert_eq!(cnt as usize, CPERMCOSET_COUNT);
 
-                memo
-            };
-        }
-        MEMO[src.0 as usize]
+    memo
+});
+impl From<CPerm> for CPermCoset {
+    fn from(src: CPerm) -> CPermCoset {
+        CPermCoset(COSET_MEMO.get()[src.0 as usize])
     }
 }
+
+pub(crate) static REPR_MEMO: Memo = Memo::new(CPERMCOSET_COUNT, || {
+    let mut memo = vec![!0; CPERMCOSET_COUNT];
+
+    for cp in CPerm::iter() {
+        let idx = CPermCoset::from(cp).0 as usize;
+        if memo[idx] == !0 {
+            memo[idx] = cp.0;
+        }
+    }
+    memo
+});
 impl From<CPermCoset> for CPerm {
     // return a representation
     fn from(src: CPermCoset) -> CPerm {
-        lazy_static! {
-            static ref MEMO: Vec<CPerm> = {
-                let mut memo = vec![CPerm(!0); CPERMCOSET_COUNT];
+        CPerm(REPR_MEMO.get()[src.0 as usize])
+    }
+}
 
-                for cp in CPerm::iter() {
-                    let idx = CPermCoset::from(cp).0 as usize;
-                    if memo[idx] == CPerm(!0) {
-                        memo[idx] = cp;
-                    }
-                }
-                memo
-            };
+pub(crate) static MOVE_MEMO: Memo = Memo::new(CPERMCOSET_COUNT * P2MOVE_COUNT, || {
+    let mut memo = vec![!0; CPERMCOSET_COUNT * P2MOVE_COUNT];
+    for (i, co) in CPermCoset::iter().enumerate() {
+        let cp: CPerm = co.into();
+
+        for m in P2Move::iter() {
+            let v: CPermCoset = (m * cp).into();
+            memo[i * P2MOVE_COUNT + (m as usize)] = v.0;
         }
-        MEMO[src.0 as usize]
     }
-}
 
+    memo
+});
 impl Mul<CPermCoset> for P2Move {
     type Output = CPermCoset;
 
     fn mul(self, rhs: CPermCoset) -> Self::Output {
-        lazy_static! {
-            static ref MEMO: Vec<CPermCoset> = {
-                let mut memo = vec![CPermCoset(!0); CPERMCOSET_COUNT * P2MOVE_COUNT];
-                for (i, co) in CPermCoset::iter().enumerate() {
-                    let cp: CPerm = co.into();
-
-                    for m in P2Move::iter() {
-                        let v: CPermCoset = (m * cp).into();
-                        memo[i * P2MOVE_COUNT + (m as usize)] = v;
-                    }
-                }
-
-                memo
-            };
-        }
-        MEMO[rhs.0 as usize * P2MOVE_COUNT + self as usize]
+        CPermCoset(MOVE_MEMO.get()[rhs.0 as usize * P2MOVE_COUNT + self as usize])
     }
 }
 
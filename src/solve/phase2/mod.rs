@@ -1,7 +1,7 @@
-mod cperm;
-mod cpermcoset;
-mod eperm;
-mod udslice;
+pub(crate) mod cperm;
+pub(crate) mod cpermcoset;
+pub(crate) mod eperm;
+pub(crate) mod udslice;
 
 pub mod p2move;
 
@@ -20,6 +20,7 @@ use self::p2move::P2Move;
 use crate::cube;
 use cube::Sym16;
 
+use crate::solve::util::Memo;
 use std::ops::Mul;
 
 pub const MAX_STEPS: usize = 18;
@@ -28,7 +29,7 @@ pub const MAX_STEPS: usize = 18;
 pub struct Phase2Cube(cube::CubieLevel);
 
 impl std::convert::TryFrom<cube::CubieLevel> for Phase2Cube {
-    type Error = ();
+    type Error = crate::solve::Error;
     fn try_from(src: cube::CubieLevel) -> Result<Self, Self::Error> {
         fn is_phase2(cube: cube::CubieLevel) -> bool {
             cube.0.iter().all(|c| c.o == 0)
@@ -39,8 +40,9 @@ impl std::convert::TryFrom<cube::CubieLevel> for Phase2Cube {
                     .enumerate()
                     .all(|(i, e)| i < 8 || (e.e as u16 >= 8))
         }
+        src.verify()?;
         if !is_phase2(src) {
-            return Err(());
+            return Err(crate::solve::Error::NotInSubgroup);
         }
         Ok(Phase2Cube(src))
     }
@@ -137,6 +139,11 @@ impl From<PruneCoord> for usize {
         src.0 as usize
     }
 }
+impl From<usize> for PruneCoord {
+    fn from(src: usize) -> Self {
+        PruneCoord(src as u32)
+    }
+}
 
 impl From<PruneVec> for PruneCoord {
     fn from(src: PruneVec) -> Self {
@@ -158,20 +165,18 @@ impl From<Phase2Vec> for PruneCoord {
     }
 }
 
+pub(crate) static SYM16_MEMO: Memo = Memo::new(CPERM_COUNT, || {
+    let mut memo = vec![!0; CPERM_COUNT];
+    for cp in CPerm::iter() {
+        let coset = CPerm::from(CPermCoset::from(cp));
+        memo[cp.0 as usize] = Sym16::iter().find(|&s| coset == s * cp).unwrap().0 as u16;
+    }
+    memo
+});
 impl From<CPerm> for Sym16 {
-    //FIXME
+    // the symmetry taking cp to the representation of its coset
     fn from(src: CPerm) -> Self {
-        lazy_static! {
-            static ref MEMO: Vec<Sym16> = {
-                let mut memo = vec![Sym16(!0); CPERM_COUNT];
-                for cp in CPerm::iter() {
-                    let coset = CPerm::from(CPermCoset::from(cp));
-                    memo[cp.0 as usize] = Sym16::iter().find(|&s| coset == s * cp).unwrap();
-                }
-                memo
-            };
-        }
-        MEMO[src.0 as usize]
+        Sym16(SYM16_MEMO.get()[src.0 as usize] as u8)
     }
 }
 
@@ -1,5 +1,6 @@
 use super::p2move::{P2Move, P2MOVE_COUNT};
 use crate::cube;
+use crate::solve::util::Memo;
 use cube::{Sym16, SYM16_COUNT};
 use std::ops::Mul;
 use strum::IntoEnumIterator;
@@ -59,42 +60,38 @@ fn udslice() {
     }
 }
 
+pub(crate) static MOVE_MEMO: Memo = Memo::new(COUNT * P2MOVE_COUNT, || {
+    let mut memo = vec![!0; COUNT * P2MOVE_COUNT];
+    for uds in S::iter() {
+        let cube: cube::CubieLevel = uds.into();
+        for m in P2Move::iter() {
+            let v: S = (m * cube).into();
+            memo[uds.0 as usize * P2MOVE_COUNT + (m as usize)] = v.0 as u16;
+        }
+    }
+    memo
+});
 impl Mul<S> for P2Move {
     type Output = S;
     fn mul(self, rhs: S) -> Self::Output {
-        lazy_static! {
-            static ref MEMO: Vec<S> = {
-                let mut memo = vec![S(!0); COUNT * P2MOVE_COUNT];
-                for uds in S::iter() {
-                    let cube: cube::CubieLevel = uds.into();
-                    for m in P2Move::iter() {
-                        let v: S = (m * cube).into();
-                        memo[uds.0 as usize * P2MOVE_COUNT + (m as usize)] = v;
-                    }
-                }
-                memo
-            };
-        }
-        MEMO[rhs.0 as usize * P2MOVE_COUNT + self as usize]
+        S(MOVE_MEMO.get()[rhs.0 as usize * P2MOVE_COUNT + self as usize] as u8)
     }
 }
+pub(crate) static SYM16_MEMO: Memo = Memo::new(COUNT * SYM16_COUNT, || {
+    let mut memo = vec![!0; COUNT * SYM16_COUNT];
+    for x in S::iter() {
+        let cube: cube::CubieLevel = x.into();
+        for s in Sym16::iter() {
+            let v: S = (s * cube).into();
+            memo[x.0 as usize * SYM16_COUNT + (s.0 as usize)] = v.0 as u16;
+        }
+    }
+    memo
+});
 impl Mul<S> for Sym16 {
     type Output = S;
     fn mul(self, rhs: S) -> Self::Output {
-        lazy_static! {
-            static ref MEMO: Vec<S> = {
-                let mut memo = vec![S(!0); COUNT * SYM16_COUNT];
-                for x in S::iter() {
-                    let cube: cube::CubieLevel = x.into();
-                    for s in Sym16::iter() {
-                        let v: S = (s * cube).into();
-                        memo[x.0 as usize * SYM16_COUNT + (s.0 as usize)] = v;
-                    }
-                }
-                memo
-            };
-        }
-        MEMO[rhs.0 as usize * SYM16_COUNT + self.0 as usize]
+        S(SYM16_MEMO.get()[rhs.0 as usize * SYM16_COUNT + self.0 as usize] as u8)
     }
 }
 
@@ -0,0 +1,259 @@
+use crate::solve::util::VecU2;
+use std::sync::atomic::{AtomicU8, AtomicUsize, Ordering};
+
+const UNSET: u8 = !0;
+const CHUNK: usize = 1 << 16;
+
+// reported once every depth is done
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Progress {
+    pub depth: u8,
+    pub count: usize, // entries found at this depth
+    pub filled: usize,
+    pub total: usize,
+    pub backward: bool,
+}
+
+// the depth of every position in a table, and how they add up
+#[derive(Debug, Clone, PartialEq)]
+pub struct Stats {
+    pub depths: Vec<usize>, // positions at each depth
+    pub average: f64,
+    pub fill: f64, // reached positions over the table size
+}
+
+// breadth-first distances from goal, one depth at a time, mod 3.
+// neighbours(i, f) calls f on every neighbour of i, and must be symmetric
+pub fn generate<N>(
+    size: usize,
+    goal: usize,
+    max_depth: u8,
+    threads: usize,
+    neighbours: N,
+    progress: &mut dyn FnMut(&Progress),
+) -> VecU2
+where
+    N: Fn(usize, &mut dyn FnMut(usize)) + Sync,
+{
+    let depths = fill_depths(
+        size,
+        goal,
+        max_depth,
+        threads,
+        neighbours,
+        |_, _| true,
+        progress,
+    );
+
+    let mut res = VecU2::new(3, size);
+    for (i, v) in depths.iter().enumerate() {
+        let v = v.load(Ordering::Relaxed);
+        if v != UNSET {
+            res.set(i, v % 3);
+        }
+    }
+    res
+}
+
+// recovers the exact depths of a mod 3 table, which takes about as long as generating it
+pub fn stats<N>(table: &VecU2, goal: usize, max_depth: u8, threads: usize, neighbours: N) -> Stats
+where
+    N: Fn(usize, &mut dyn FnMut(usize)) + Sync,
+{
+    let accept = |i: usize, depth: u8| table.get(i) == depth % 3;
+    let mut depths = vec![];
+    fill_depths(
+        table.size(),
+        goal,
+        max_depth,
+        threads,
+        neighbours,
+        accept,
+        &mut |p| depths.push(p.count),
+    );
+
+    let filled: usize = depths.iter().sum();
+    let total: usize = depths.iter().enumerate().map(|(d, &c)| d * c).sum();
+    Stats {
+        average: total as f64 / filled as f64,
+        fill: filled as f64 / table.size() as f64,
+        depths,
+    }
+}
+
+// checks that every entry is set, and that every entry but the goal has a neighbour one step
+// closer, which is all the search relies on; returns the first entry that is not
+pub fn verify<N>(table: &VecU2, goal: usize, threads: usize, neighbours: N) -> Result<(), usize>
+where
+    N: Fn(usize, &mut dyn FnMut(usize)) + Sync,
+{
+    let bad = |i: usize| {
+        let v = table.get(i);
+        if i == goal {
+            return v != 0;
+        }
+        let mut down = false;
+        neighbours(i, &mut |n| down = down || table.get(n) == (v + 2) % 3);
+        v == 3 || !down
+    };
+
+    let first = AtomicUsize::new(usize::MAX);
+    scan(table.size(), threads, &|i| {
+        if i < first.load(Ordering::Relaxed) && bad(i) {
+            first.fetch_min(i, Ordering::Relaxed);
+        }
+        0
+    });
+    match first.into_inner() {
+        usize::MAX => Ok(()),
+        i => Err(i),
+    }
+}
+
+// the table is scanned in chunks across threads: forward expands the entries at the current
+// depth, backward looks for a neighbour at the current depth from every unset entry,
+// which is cheaper once fewer entries are left than were just found.
+// only entries accept(entry, depth) are given a depth
+fn fill_depths<N, A>(
+    size: usize,
+    goal: usize,
+    max_depth: u8,
+    threads: usize,
+    neighbours: N,
+    accept: A,
+    progress: &mut dyn FnMut(&Progress),
+) -> Vec<AtomicU8>
+where
+    N: Fn(usize, &mut dyn FnMut(usize)) + Sync,
+    A: Fn(usize, u8) -> bool + Sync,
+{
+    let table: Vec<AtomicU8> = (0..size).map(|_| AtomicU8::new(UNSET)).collect();
+    table[goal].store(0, Ordering::Relaxed);
+
+    let mut filled = 1;
+    let mut count = 1;
+    progress(&Progress {
+        depth: 0,
+        count,
+        filled,
+        total: size,
+        backward: false,
+    });
+
+    for depth in 0..max_depth {
+        let backward = size - filled < count;
+        let step = |i: usize| -> usize {
+            let cur = table[i].load(Ordering::Relaxed);
+            let mut found = 0;
+            if backward && cur == UNSET && accept(i, depth + 1) {
+                let mut hit = false;
+                neighbours(i, &mut |n| {
+                    hit = hit || table[n].load(Ordering::Relaxed) == depth;
+                });
+                if hit {
+                    table[i].store(depth + 1, Ordering::Relaxed);
+                    found += 1;
+                }
+            } else if !backward && cur == depth {
+                neighbours(i, &mut |n| {
+                    if !accept(n, depth + 1) {
+                        return;
+                    }
+                    let set = table[n].compare_exchange(
+                        UNSET,
+                        depth + 1,
+                        Ordering::Relaxed,
+                        Ordering::Relaxed,
+                    );
+                    found += set.is_ok() as usize;
+                });
+            }
+            found
+        };
+
+        count = scan(size, threads, &step);
+        if count == 0 {
+            break;
+        }
+        filled += count;
+        progress(&Progress {
+            depth: depth + 1,
+            count,
+            filled,
+            total: size,
+            backward,
+        });
+    }
+    table
+}
+
+// sum of step over 0..size, handing out chunks to the threads
+fn scan<F: Fn(usize) -> usize + Sync>(size: usize, threads: usize, step: &F) -> usize {
+    let next = AtomicUsize::new(0);
+    let work = || {
+        let mut res = 0;
+        loop {
+            let start = next.fetch_add(CHUNK, Ordering::Relaxed);
+            if start >= size {
+                return res;
+            }
+            res += (start..(start + CHUNK).min(size)).map(step).sum::<usize>();
+        }
+    };
+
+    std::thread::scope(|scope| {
+        let workers: Vec<_> = (1..threads.max(1)).map(|_| scope.spawn(work)).collect();
+        let res = work();
+        res + workers
+            .into_iter()
+            .map(|w| w.join().unwrap())
+            .sum::<usize>()
+    })
+}
+
+pub fn default_threads() -> usize {
+    std::thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+#[test]
+fn generate_small() {
+    // a cycle, the two halves meeting opposite the goal
+    const N: usize = 400;
+    let neighbours = |i: usize, f: &mut dyn FnMut(usize)| {
+        f((i + 1) % N);
+        f((i + N - 1) % N);
+    };
+    let mut log = vec![];
+    let table = generate(N, 7, 254, 3, neighbours, &mut |p| log.push(p.clone()));
+
+    for i in 0..N {
+        let dist = (i + N - 7) % N;
+        let dist = dist.min(N - dist);
+        assert_eq!(table.get(i) as usize, dist % 3);
+    }
+    assert_eq!(log.len(), N / 2 + 1);
+    assert_eq!(log.last().unwrap().filled, N);
+    // 2 found at every depth, so the last one only has 1 unset entry to look at
+    assert!(log.last().unwrap().backward);
+
+    // a hypercube, where depth d is the C(16, d) entries with d bits set
+    let neighbours = |i: usize, f: &mut dyn FnMut(usize)| (0..16).for_each(|b| f(i ^ (1 << b)));
+    let mut log = vec![];
+    let table = generate(1 << 16, 0, 254, 2, neighbours, &mut |p| log.push(p.clone()));
+    for i in 0..1 << 16 {
+        assert_eq!(table.get(i) as u32, (i as u32).count_ones() % 3);
+    }
+    let counts: Vec<_> = log.iter().map(|p| p.count).collect();
+    assert_eq!(counts[..4], [1, 16, 120, 560]);
+    assert!(!log[10].backward && log[11].backward);
+
+    let stats = stats(&table, 0, 254, 2, neighbours);
+    assert_eq!(stats.depths, counts);
+    assert_eq!(stats.average, 8.0);
+    assert_eq!(stats.fill, 1.0);
+    assert_eq!(verify(&table, 0, 2, neighbours), Ok(()));
+
+    let mut table = table;
+    table.set(0b1011, 1);
+    assert_eq!(verify(&table, 0, 2, neighbours), Err(0b1011));
+}
@@ -1,167 +1,312 @@
 use crate::cube;
+use crate::solve::{Budget, Error, SolveOptions};
 use cube::Move;
-use num_traits::{FromPrimitive, ToPrimitive};
 use std::convert::{TryFrom, TryInto};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Mutex;
 
 pub trait Solver {
     type Error;
-    fn solve(&self, cubie: cube::CubieLevel) -> Result<Vec<Move>, Self::Error>;
+    fn solve(&self, cubie: cube::CubieLevel) -> Result<Vec<Move>, Self::Error> {
+        self.solve_with(cubie, &SolveOptions::default())
+    }
+
+    fn solve_with(
+        &self,
+        cubie: cube::CubieLevel,
+        opts: &SolveOptions,
+    ) -> Result<Vec<Move>, Self::Error>;
+
+    // moves taking from to to, by solving from * to^-1
+    fn solve_between(
+        &self,
+        from: &cube::CubieLevel,
+        to: &cube::CubieLevel,
+    ) -> Result<Vec<Move>, Self::Error> {
+        self.solve(from * &to.inverse())
+    }
 }
 
 pub trait Kociemba {
-    type S;
-    type M;
-    type Cube;
-    type Coord;
-    type PruneCoord;
+    type S: StaticExactSizeIterator + std::ops::Mul<Self::Coord, Output = Self::Coord> + Copy;
+    type M: StaticExactSizeIterator
+        + std::ops::Mul<Self::Coord, Output = Self::Coord>
+        + Into<Move>
+        + Copy;
+    type Cube: TryFrom<cube::CubieLevel, Error = Error>;
+    type Coord: From<Self::PruneCoord>
+        + From<Self::Cube>
+        + std::cmp::Eq
+        + std::cmp::Ord
+        + std::hash::Hash
+        + Copy;
+    type PruneCoord: From<Self::Coord>
+        + Into<usize>
+        + std::cmp::Eq
+        + std::cmp::Ord
+        + std::hash::Hash
+        + Copy;
 
     fn prunetable_get(&self, idx: usize) -> usize;
 
     fn SOLVED_COORD() -> Self::Coord; // const SOLVED_COORD: Self::Coord;
     const MAX_STEPS: usize;
 
-    fn solve(
-        &self,
-        cubie: cube::CubieLevel,
-    ) -> Result<Vec<Move>, <Self::Cube as std::convert::TryFrom<cube::CubieLevel>>::Error>
-    where
-        Self::Cube: std::convert::TryFrom<cube::CubieLevel>,
-        Self::Coord: std::convert::From<Self::PruneCoord>
-            + std::convert::From<Self::Cube>
-            + std::cmp::Eq
-            + std::cmp::Ord
-            + std::hash::Hash
-            + Copy,
-        Self::PruneCoord:
-            std::convert::From<Self::Coord> + std::cmp::Eq + std::cmp::Ord + std::hash::Hash + Copy,
-        usize: std::convert::From<Self::PruneCoord>,
-        Self::S: StaticExactSizeIterator + std::ops::Mul<Self::Coord, Output = Self::Coord> + Copy,
-        Self::M: StaticExactSizeIterator
-            + num_traits::FromPrimitive
-            + num_traits::ToPrimitive
-            + std::ops::Mul<Self::Coord, Output = Self::Coord>
-            + Copy,
-        Move: std::convert::From<Self::M>,
-    {
-        let cube: Self::Cube = cubie.try_into()?;
-
-        use std::collections::{BinaryHeap, HashSet};
+    fn lower_bound(&self, src: Self::Coord) -> Result<u8, Error> {
+        self.prune_distance(src.into())
+    }
 
+    // exact depth of pc, walking down the mod-3 values to the goal
+    fn prune_distance(&self, pc: Self::PruneCoord) -> Result<u8, Error> {
         let solved: Self::Coord = Self::SOLVED_COORD();
-        let src: Self::Coord = cube.into();
+        let goalpc: Self::PruneCoord = solved.into();
 
-        let lb = (|src: Self::Coord| -> u8 {
-            let src: Self::PruneCoord = src.into();
+        let mut pc = pc;
+        let mut dist = 0u8;
+        while pc != goalpc {
+            let cur = Self::Coord::from(pc);
+            let dec = (self.prunetable_get(pc.into()) + 2) % 3;
 
-            let mut set = HashSet::new();
-            let mut heap = BinaryHeap::new();
-            heap.push((-0i8, src));
-            set.insert(src);
+            pc = Self::S::iter()
+                .flat_map(|s| Self::M::iter().map(move |m| m * (s * cur)))
+                .map(Self::PruneCoord::from)
+                .find(|&npc| self.prunetable_get(npc.into()) == dec)
+                .ok_or(Error::TableCorrupt)?;
+            dist = dist.checked_add(1).ok_or(Error::TableCorrupt)?;
+        }
+        Ok(dist)
+    }
 
-            let solved: Self::Coord = Self::SOLVED_COORD();
-            let goalpc: Self::PruneCoord = solved.into();
+    // the next lower bound, from the mod-3 table entry of the neighbour
+    fn next_lower_bound(&self, lb: u8, next: Self::Coord) -> Result<u8, Error> {
+        match self.prunetable_get(Self::PruneCoord::from(next).into()) as u8 {
+            i if i == lb % 3 => Ok(lb),
+            i if i == (lb + 1) % 3 => Ok(lb + 1),
+            i if i == (lb + 2) % 3 => lb.checked_sub(1).ok_or(Error::TableCorrupt),
+            _ => Err(Error::TableCorrupt),
+        }
+    }
 
-            while let Some((dist, pc)) = heap.pop() {
-                let dist = -dist;
-                if pc == goalpc {
-                    return dist as u8;
-                }
+    // IDA*: deepen the bound from the exact lower bound of src up to max_steps.
+    // last is the move already made before src, which the first move must not repeat
+    fn search(
+        &self,
+        src: Self::Coord,
+        last: Option<Move>,
+        max_steps: usize,
+        budget: &Budget,
+    ) -> Result<Option<Vec<Move>>, Error> {
+        let lb = self.lower_bound(src)?;
 
-                let cur: Self::Coord = pc.into();
-                let dec = (self.prunetable_get(pc.into()) + 2) % 3;
+        let mut path = Vec::with_capacity(max_steps);
+        for depth in lb as usize..=max_steps {
+            if self.search_depth(src, lb, depth, last, &mut path, budget)? {
+                return Ok(Some(path));
+            }
+        }
 
-                for s in Self::S::iter() {
-                    let cur = s * cur;
+        Ok(None)
+    }
 
-                    for m in Self::M::iter() {
-                        let cur = m * cur;
+    fn search_depth(
+        &self,
+        cur: Self::Coord,
+        lb: u8,
+        togo: usize,
+        last: Option<Move>,
+        path: &mut Vec<Move>,
+        budget: &Budget,
+    ) -> Result<bool, Error> {
+        budget.tick()?;
+        if togo == 0 {
+            return Ok(cur == Self::SOLVED_COORD());
+        }
 
-                        let npc: Self::PruneCoord = cur.into();
+        for m in Self::M::iter() {
+            let mv: Move = m.into();
+            if is_redundant(path.last().cloned().or(last), mv) {
+                continue;
+            }
 
-                        if set.contains(&npc) {
-                            continue;
-                        }
+            let next = m * cur;
+            let nlb = self.next_lower_bound(lb, next)?;
+            if nlb as usize >= togo {
+                continue;
+            }
 
-                        if self.prunetable_get(npc.into()) == dec {
-                            heap.push((-(dist + 1), npc));
-                            set.insert(npc);
-                        }
-                    }
-                }
+            path.push(mv);
+            if self.search_depth(next, nlb, togo - 1, last, path, budget)? {
+                return Ok(true);
             }
-            unreachable!("broken prunetable")
-        })(src);
+            path.pop();
+        }
+        Ok(false)
+    }
+
+    fn solve_with(&self, cubie: cube::CubieLevel, opts: &SolveOptions) -> Result<Vec<Move>, Error> {
+        let cube: Self::Cube = cubie.try_into()?;
+        self.search(cube.into(), None, Self::MAX_STEPS, &Budget::new(opts))?
+            .ok_or(Error::BudgetExceeded)
+    }
+
+    // every solution up to max_len, shortest first
+    fn solutions(
+        &self,
+        cubie: cube::CubieLevel,
+        max_len: usize,
+    ) -> Result<Solutions<'_, Self>, Error>
+    where
+        Self: Sized,
+    {
+        let cube: Self::Cube = cubie.try_into()?;
+        let src: Self::Coord = cube.into();
+        let lb = self.lower_bound(src)?;
 
-        let mut heap = BinaryHeap::new();
-        let mut set = HashSet::new();
+        let mut res = Solutions {
+            solver: self,
+            moves: Self::M::iter().collect(),
+            max_len,
+            depth: lb as usize,
+            src: (src, lb),
+            stack: vec![],
+            path: vec![],
+        };
+        if res.depth <= max_len {
+            res.stack.push((src, lb, 0));
+        }
+        Ok(res)
+    }
+}
 
-        heap.push((-0i8, src, lb, 0));
-        set.insert(src);
+// IDA* with an explicit stack, yielding every path of the current depth.
+// a corrupt table entry ends it with an error
+pub struct Solutions<'a, K: Kociemba> {
+    solver: &'a K,
+    moves: Vec<K::M>,
+    max_len: usize,
+    depth: usize,
+    src: (K::Coord, u8),
+    stack: Vec<(K::Coord, u8, usize)>, // coord, lower bound, next move index
+    path: Vec<Move>,
+}
 
-        while let Some((dist, state, lb, rotates)) = heap.pop() {
-            let dist = -dist;
-            let dist = dist as u8;
+impl<'a, K: Kociemba> Iterator for Solutions<'a, K> {
+    type Item = Result<Vec<Move>, Error>;
 
-            if state == solved {
-                let dist = dist as usize;
-                let rotates = {
-                    let mut rotates = rotates;
-                    let mut res = vec![Move::U1; dist];
+    fn next(&mut self) -> Option<Self::Item> {
+        let solved = K::SOLVED_COORD();
 
-                    for i in 0..dist {
-                        let mv = Self::M::from_usize(rotates as usize % Self::M::COUNT).unwrap();
-                        res[dist - 1 - i] = mv.into();
-                        rotates /= Self::M::COUNT as u64;
+        loop {
+            let (cur, lb, idx) = match self.stack.last_mut() {
+                Some(top) => top,
+                None => {
+                    self.depth += 1;
+                    if self.depth > self.max_len {
+                        return None;
                     }
-                    res
+                    let (src, lb) = self.src;
+                    self.stack.push((src, lb, 0));
+                    continue;
+                }
+            };
+            let togo = self.depth - self.path.len();
+
+            if togo == 0 || *idx == self.moves.len() {
+                let res = if togo == 0 && *cur == solved {
+                    Some(Ok(self.path.clone()))
+                } else {
+                    None
                 };
-                return Ok(rotates);
+                self.stack.pop();
+                self.path.pop();
+                if res.is_some() {
+                    return res;
+                }
+                continue;
             }
 
-            for m in Self::M::iter() {
-                let nstate = m * state;
-
-                let nlb = match self.prunetable_get(Self::PruneCoord::from(nstate).into()) as u8 {
-                    i if i == lb % 3 => lb,
-                    i if i == (lb + 1) % 3 => lb + 1,
-                    i if i == (lb + 2) % 3 => lb - 1,
-                    _ => unreachable!(),
-                };
+            let m = self.moves[*idx];
+            *idx += 1;
 
-                if dist + 1 + nlb >= Self::MAX_STEPS as u8 {
-                    continue;
-                }
+            let mv: Move = m.into();
+            if is_redundant(self.path.last().cloned(), mv) {
+                continue;
+            }
 
-                if set.contains(&nstate) {
-                    continue;
+            let next = m * *cur;
+            let nlb = match self.solver.next_lower_bound(*lb, next) {
+                Ok(nlb) => nlb,
+                Err(e) => {
+                    self.stack.clear();
+                    self.max_len = self.depth;
+                    return Some(Err(e));
                 }
-                set.insert(nstate);
-
-                heap.push((
-                    -(dist as i8 + 1),
-                    nstate,
-                    nlb,
-                    rotates * Self::M::COUNT as u64 + m.to_u64().unwrap(),
-                ));
+            };
+            // a solution passing through the goal has a shorter prefix
+            if nlb as usize >= togo || (next == solved && togo > 1) {
+                continue;
             }
-        }
 
-        unreachable!();
+            self.path.push(mv);
+            self.stack.push((next, nlb, 0));
+        }
     }
 }
 
 use crate::solve;
-use solve::phase2;
+use solve::{phase1, phase2};
 
+use phase1::{Phase1Coord, Phase1Cube};
 use phase2::p2move::*;
 use phase2::*;
 
+use crate::solve::prune::{self, Progress, Stats};
+use crate::solve::table::{self, TableKind};
 use crate::solve::util::VecU2;
-use cube::Sym16;
+use cube::{Sym1, Sym16, Sym48};
+
+// what the pruning tables of the two phases differ in
+pub trait PruningTable {
+    const KIND: TableKind;
+    const SIZES: [u64; 2];
+    const MAX_DEPTH: u8;
 
-#[derive(Deserialize, Serialize)]
-pub struct Phase2Solver {
-    prunetable: VecU2, // CPERMCOSET_COUNT * EPERM_COUNT
+    // calls f on every entry one move away from entry i
+    fn neighbours(i: usize, f: &mut dyn FnMut(usize));
+}
+
+#[derive(Clone)]
+pub struct Phase1Table;
+#[derive(Clone)]
+pub struct Phase2Table;
+
+#[derive(Clone)]
+pub struct PruningSolver<T> {
+    prunetable: VecU2, // T::SIZES[0] * T::SIZES[1]
+    _table: std::marker::PhantomData<T>,
+}
+
+pub type Phase1Solver = PruningSolver<Phase1Table>;
+pub type Phase2Solver = PruningSolver<Phase2Table>;
+
+impl Kociemba for Phase1Solver {
+    type S = Sym1;
+    type M = Move;
+    type Cube = Phase1Cube;
+    type Coord = Phase1Coord;
+    type PruneCoord = phase1::PruneCoord;
+
+    fn prunetable_get(&self, idx: usize) -> usize {
+        self.prunetable.get(idx) as usize
+    }
+
+    fn SOLVED_COORD() -> Self::Coord {
+        lazy_static! {
+            static ref RES: Phase1Coord = Phase1Cube::try_from(cube::SOLVED).unwrap().into();
+        }
+        *RES
+    }
+    const MAX_STEPS: usize = phase1::MAX_STEPS;
 }
 
 impl Kociemba for Phase2Solver {
@@ -185,112 +330,429 @@ impl Kociemba for Phase2Solver {
     const MAX_STEPS: usize = 18;
 }
 
-impl<T: Kociemba> Solver for T
-where
-    // FIXME duplication
-    <T as Kociemba>::Cube: std::convert::TryFrom<cube::CubieLevel>,
-    <T as Kociemba>::Cube: std::convert::TryFrom<cube::CubieLevel>,
-    <T as Kociemba>::Coord: std::convert::From<<T as Kociemba>::PruneCoord>
-        + std::convert::From<<T as Kociemba>::Cube>
-        + std::cmp::Eq
-        + std::cmp::Ord
-        + std::hash::Hash
-        + Copy,
-    <T as Kociemba>::PruneCoord: std::convert::From<<T as Kociemba>::Coord>
-        + std::cmp::Eq
-        + std::cmp::Ord
-        + std::hash::Hash
-        + Copy,
-    usize: std::convert::From<<T as Kociemba>::PruneCoord>,
-    <T as Kociemba>::S: StaticExactSizeIterator
-        + std::ops::Mul<<T as Kociemba>::Coord, Output = <T as Kociemba>::Coord>
-        + Copy,
-    <T as Kociemba>::M: StaticExactSizeIterator
-        + num_traits::FromPrimitive
-        + num_traits::ToPrimitive
-        + std::ops::Mul<<T as Kociemba>::Coord, Output = <T as Kociemba>::Coord>
-        + Copy,
-    Move: std::convert::From<<T as Kociemba>::M>,
-{
-    type Error = <<T as Kociemba>::Cube as std::convert::TryFrom<cube::CubieLevel>>::Error;
+impl<T: Kociemba> Solver for T {
+    type Error = Error;
 
-    fn solve(&self, cubie: cube::CubieLevel) -> Result<Vec<Move>, Self::Error> {
-        self.solve(cubie)
+    fn solve_with(
+        &self,
+        cubie: cube::CubieLevel,
+        opts: &SolveOptions,
+    ) -> Result<Vec<Move>, Self::Error> {
+        Kociemba::solve_with(self, cubie, opts)
+    }
+}
+
+impl PruningTable for Phase1Table {
+    const KIND: TableKind = TableKind::Phase1;
+    const SIZES: [u64; 2] = [
+        phase1::FLIPSLICE_CLASS_COUNT as u64,
+        phase1::CTWIST_COUNT as u64,
+    ];
+    const MAX_DEPTH: u8 = phase1::MAX_STEPS as u8;
+
+    fn neighbours(i: usize, f: &mut dyn FnMut(usize)) {
+        let cur: Phase1Coord = phase1::PruneCoord::from(i).into();
+        for m in Move::iter() {
+            for t in phase1::PruneVec::from(m * cur).equivalents() {
+                f(phase1::PruneCoord::from(t).into());
+            }
+        }
+    }
+}
+
+impl PruningTable for Phase2Table {
+    const KIND: TableKind = TableKind::Phase2;
+    const SIZES: [u64; 2] = [CPERMCOSET_COUNT as u64, EPERM_COUNT as u64];
+    const MAX_DEPTH: u8 = MAX_STEPS as u8;
+
+    fn neighbours(i: usize, f: &mut dyn FnMut(usize)) {
+        let cur: Phase2Vec = PruneVec::from(PruneCoord::from(i)).into();
+        for s in Sym16::iter() {
+            let cur = s * cur;
+            for m in P2Move::iter() {
+                let t: PruneCoord = PruneVec::from(m * cur).into();
+                f(t.into());
+            }
+        }
     }
 }
 
-impl Phase2Solver {
-    pub fn new_from_cache<R>(src: R) -> Result<Self, ()>
+impl<T: PruningTable> PruningSolver<T>
+where
+    Self: Kociemba,
+{
+    fn from_table(prunetable: VecU2) -> Self {
+        PruningSolver {
+            prunetable,
+            _table: std::marker::PhantomData,
+        }
+    }
+
+    pub fn new_from_cache<R>(src: R) -> Result<Self, Error>
     where
         R: std::io::Read,
     {
-        use crate::hash::{Digest, DigestWriter};
-        use crate::tee::TeeReader;
-        use std::hash::Hasher;
-
-        let mut hasher = Digest::new(0xEDB88320);
-        let hashwriter = DigestWriter::new(&mut hasher);
-        let reader = TeeReader::new(src, hashwriter);
-        let result = bincode::deserialize_from(reader).or(Err(()))?;
-        let hash = hasher.finish();
-        // SHA256 hex!("562673e1f32373e41d653ec89967d5367924388812ca5f9a3245e2ec9be4f02c")
-        if hash == 0x39e61d6c {
-            return Ok(result);
-        }
-        Err(())
+        table::read_table(src, T::KIND, T::SIZES).map(Self::from_table)
     }
 
-    pub fn new() -> Self {
-        let mut rawtable = vec![!0u8; CPERMCOSET_COUNT * EPERM_COUNT];
-        {
-            let mut queue = std::collections::VecDeque::with_capacity(37144996);
+    // zero-copy: entries are read straight out of the mapped file
+    pub fn new_from_mmap(file: &std::fs::File) -> Result<Self, Error> {
+        table::map_table(file, T::KIND, T::SIZES).map(Self::from_table)
+    }
 
-            let solved: Phase2Coord = Self::SOLVED_COORD();
-            let solved: PruneCoord = solved.into();
+    pub fn write_cache<W>(&self, dst: W) -> Result<(), Error>
+    where
+        W: std::io::Write,
+    {
+        table::write_table(dst, T::KIND, T::SIZES, &self.prunetable)
+    }
 
-            queue.push_back(solved);
-            rawtable[usize::from(solved)] = 0;
+    pub fn build(threads: usize, progress: &mut dyn FnMut(&Progress)) -> Self {
+        let prunetable = prune::generate(
+            (T::SIZES[0] * T::SIZES[1]) as usize,
+            Self::goal(),
+            T::MAX_DEPTH,
+            threads,
+            T::neighbours,
+            progress,
+        );
+        Self::from_table(prunetable)
+    }
 
-            while let Some(pc) = queue.pop_front() {
-                let dis: u8 = rawtable[usize::from(pc)];
-                if dis >= MAX_STEPS as u8 {
-                    break;
-                }
+    pub fn stats(&self) -> Stats {
+        prune::stats(
+            &self.prunetable,
+            Self::goal(),
+            T::MAX_DEPTH,
+            prune::default_threads(),
+            T::neighbours,
+        )
+    }
 
-                let cur: Phase2Vec = PruneVec::from(pc).into();
+    pub fn verify(&self) -> Result<(), Error> {
+        prune::verify(
+            &self.prunetable,
+            Self::goal(),
+            prune::default_threads(),
+            T::neighbours,
+        )
+        .map_err(Error::TableInconsistent)
+    }
 
-                for s in Sym16::iter() {
-                    let cur = s * cur;
+    fn goal() -> usize {
+        <Self as Kociemba>::PruneCoord::from(Self::SOLVED_COORD()).into()
+    }
+}
+
+pub struct TwoPhaseSolver {
+    phase1: Phase1Solver,
+    phase2: Phase2Solver,
+    max_length: usize,
+    threads: usize,
+}
+
+// state shared by the threads of one two-phase search
+struct Phase1Search<'a> {
+    cubie: cube::CubieLevel,
+    best: &'a Mutex<Option<Vec<Move>>>,
+    budget: &'a Budget<'a>,
+    // this thread only tries the first moves m with m % threads == thread
+    thread: usize,
+    threads: usize,
+}
+
+impl<'a> Phase1Search<'a> {
+    fn best_len(&self) -> Option<usize> {
+        self.best.lock().unwrap().as_ref().map(Vec::len)
+    }
+}
 
-                    for m in P2Move::iter() {
-                        let cur = m * cur;
+impl TwoPhaseSolver {
+    pub fn new(phase1: Phase1Solver, phase2: Phase2Solver, max_length: usize) -> Self {
+        Self {
+            phase1,
+            phase2,
+            max_length,
+            threads: 1,
+        }
+    }
 
-                        let t: PruneCoord = PruneVec::from(cur).into();
-                        let coord: usize = t.into();
+    // solve_all runs one cube per thread; a single solve is split by its first move,
+    // so it uses at most 18 of them
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
 
-                        if rawtable[coord] == !0 {
-                            rawtable[coord] = dis + 1;
-                            queue.push_back(t);
+    // solves each cube single-threaded, spreading the cubes over the threads
+    pub fn solve_all(
+        &self,
+        cubes: &[cube::CubieLevel],
+        opts: &SolveOptions,
+    ) -> Vec<Result<Vec<Move>, Error>> {
+        let next = AtomicUsize::new(0);
+        let mut res: Vec<_> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..self.threads.min(cubes.len()))
+                .map(|_| {
+                    scope.spawn(|| {
+                        let mut res = vec![];
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            if i >= cubes.len() {
+                                return res;
+                            }
+                            res.push((i, self.solve_single(cubes[i], opts)));
                         }
-                    }
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|w| w.join().unwrap())
+                .collect()
+        });
+        res.sort_by_key(|&(i, _)| i);
+        res.into_iter().map(|(_, r)| r).collect()
+    }
+
+    fn solve_single(
+        &self,
+        cubie: cube::CubieLevel,
+        opts: &SolveOptions,
+    ) -> Result<Vec<Move>, Error> {
+        let src: Phase1Coord = Phase1Cube::try_from(cubie)?.into();
+        let lb = self.phase1.lower_bound(src)?;
+        let best = Mutex::new(None);
+        let budget = Budget::new(opts);
+        let search = Phase1Search {
+            cubie,
+            best: &best,
+            budget: &budget,
+            thread: 0,
+            threads: 1,
+        };
+        let res = self.phase1_deepen(&search, src, lb);
+        self.finish(best, res)
+    }
+
+    fn solve_parallel(
+        &self,
+        cubie: cube::CubieLevel,
+        opts: &SolveOptions,
+    ) -> Result<Vec<Move>, Error> {
+        let src: Phase1Coord = Phase1Cube::try_from(cubie)?.into();
+        let lb = self.phase1.lower_bound(src)?;
+        let best = Mutex::new(None);
+        let stop = AtomicBool::new(false);
+        let budget = Budget::with_stop(opts, &stop);
+        let threads = self.threads.min(Move::iter().len());
+
+        let results: Vec<_> = std::thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|thread| {
+                    let (best, stop, budget) = (&best, &stop, &budget);
+                    scope.spawn(move || {
+                        let search = Phase1Search {
+                            cubie,
+                            best,
+                            budget,
+                            thread,
+                            threads,
+                        };
+                        let res = self.phase1_deepen(&search, src, lb);
+                        if !matches!(res, Ok(false)) {
+                            stop.store(true, Ordering::Relaxed);
+                        }
+                        res
+                    })
+                })
+                .collect();
+            workers.into_iter().map(|w| w.join().unwrap()).collect()
+        });
+
+        if opts.cancel.as_ref().is_some_and(|c| c.is_cancelled()) {
+            return Err(Error::Cancelled);
+        }
+        // a thread stopped by another one reports Cancelled
+        let res = results
+            .into_iter()
+            .filter(|r| !matches!(r, Err(Error::Cancelled)))
+            .find(Result::is_err)
+            .unwrap_or(Ok(false));
+        self.finish(best, res)
+    }
+
+    // a solution within max_length is returned whatever stopped the search; once the deadline
+    // or the node limit is hit, so is the best one so far even if it is longer
+    fn finish(
+        &self,
+        best: Mutex<Option<Vec<Move>>>,
+        res: Result<bool, Error>,
+    ) -> Result<Vec<Move>, Error> {
+        let best = best.into_inner().unwrap();
+        match res {
+            _ if best.as_ref().is_some_and(|b| b.len() <= self.max_length) => Ok(best.unwrap()),
+            Err(Error::BudgetExceeded) => best.ok_or(Error::BudgetExceeded),
+            Err(e) => Err(e),
+            Ok(_) => Err(Error::BudgetExceeded),
+        }
+    }
+
+    // iterative deepening of phase 1, returning true once a solution within max_length is found
+    fn phase1_deepen(
+        &self,
+        search: &Phase1Search,
+        src: Phase1Coord,
+        lb: u8,
+    ) -> Result<bool, Error> {
+        let mut path = vec![];
+        // phase 1 alone may need more moves than its pruning table is deep
+        for depth in lb..=self.max_length.min(u8::MAX as usize) as u8 {
+            if search.best_len().is_some_and(|len| depth as usize >= len) {
+                break;
+            }
+            // the cube may be in G1 already, which only the first thread checks
+            if depth == 0 && search.thread != 0 {
+                continue;
+            }
+            if self.phase1_search(search, src, lb, depth, &mut path)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    // returns true once a solution within max_length is found
+    fn phase1_search(
+        &self,
+        search: &Phase1Search,
+        cur: Phase1Coord,
+        lb: u8,
+        togo: u8,
+        path: &mut Vec<Move>,
+    ) -> Result<bool, Error> {
+        search.budget.tick()?;
+        if togo == 0 {
+            if cur != Phase1Solver::SOLVED_COORD() {
+                return Ok(false);
+            }
+            // a phase 1 solution ending with a phase 2 move has already been tried shorter
+            if let Some(&m) = path.last() {
+                if m.face() < 2 || m as usize % 3 == 1 {
+                    return Ok(false);
+                }
+            }
+
+            // another thread may have found one no longer than this phase 1 part by now
+            let limit = match search.best_len() {
+                Some(len) => len.checked_sub(path.len() + 1),
+                None => Some(Phase2Solver::MAX_STEPS),
+            };
+            let limit = match limit {
+                Some(limit) => limit.min(Phase2Solver::MAX_STEPS),
+                None => return Ok(search.best_len().is_some_and(|len| len <= self.max_length)),
+            };
+
+            let cube = search.cubie.apply_all(path);
+            let cube: Phase2Coord = Phase2Cube::try_from(cube)?.into();
+            let last = path.last().cloned();
+            if let Some(moves) = self.phase2.search(cube, last, limit, search.budget)? {
+                let mut best = search.best.lock().unwrap();
+                // another thread may have found a shorter one meanwhile
+                if best
+                    .as_ref()
+                    .is_none_or(|b| path.len() + moves.len() < b.len())
+                {
+                    let mut res = path.clone();
+                    res.extend(moves);
+                    *best = Some(res);
                 }
             }
+            return Ok(search.best_len().is_some_and(|len| len <= self.max_length));
         }
 
-        let mut p2 = Self {
-            prunetable: VecU2::new(3, CPERMCOSET_COUNT * EPERM_COUNT),
-        };
+        for m in Move::iter() {
+            if path.is_empty() && m as usize % search.threads != search.thread {
+                continue;
+            }
+            if is_redundant(path.last().cloned(), m) {
+                continue;
+            }
+
+            let next = m * cur;
+            let nlb = self.phase1.next_lower_bound(lb, next)?;
+            if nlb >= togo {
+                continue;
+            }
 
-        for (i, &v) in rawtable.iter().enumerate() {
-            if v != !0 {
-                p2.prunetable.set(i, v as u8 % 3);
+            path.push(m);
+            if self.phase1_search(search, next, nlb, togo - 1, path)? {
+                return Ok(true);
             }
+            path.pop();
         }
+        Ok(false)
+    }
+}
+
+impl Solver for TwoPhaseSolver {
+    type Error = Error;
 
-        p2
+    // on a deadline or node limit, the best solution so far is returned
+    fn solve_with(
+        &self,
+        cubie: cube::CubieLevel,
+        opts: &SolveOptions,
+    ) -> Result<Vec<Move>, Self::Error> {
+        if self.threads > 1 {
+            self.solve_parallel(cubie, opts)
+        } else {
+            self.solve_single(cubie, opts)
+        }
     }
 }
 
+impl TwoPhaseSolver {
+    // distinct solutions up to max_len, grouped by their phase 1 part
+    pub fn solutions(
+        &self,
+        cubie: cube::CubieLevel,
+        max_len: usize,
+    ) -> Result<impl Iterator<Item = Result<Vec<Move>, Error>> + '_, Error> {
+        let phase1 = self.phase1.solutions(cubie, max_len)?;
+
+        Ok(phase1.flat_map(move |path| {
+            let path = match path {
+                Ok(path) => path,
+                Err(e) => return Box::new(std::iter::once(Err(e))) as Box<dyn Iterator<Item = _>>,
+            };
+            let cube = cubie.apply_all(&path);
+            let res: Box<dyn Iterator<Item = _>> =
+                match self.phase2.solutions(cube, max_len - path.len()) {
+                    Ok(phase2) => {
+                        let last = path.last().cloned();
+                        Box::new(
+                            phase2
+                                .filter(move |moves| {
+                                    !matches!(moves, Ok(moves) if moves.first().is_some_and(|&m| is_redundant(last, m)))
+                                })
+                                .map(move |moves| moves.map(|moves| [&path[..], &moves[..]].concat())),
+                        )
+                    }
+                    Err(e) => Box::new(std::iter::once(Err(e))),
+                };
+            res
+        }))
+    }
+}
+
+// same face twice in a row, or commuting opposite faces out of order
+fn is_redundant(last: Option<Move>, m: Move) -> bool {
+    last.is_some_and(|last| {
+        m.face() == last.face() || (m.axis() == last.axis() && m.face() < last.face())
+    })
+}
+
 pub trait StaticExactSizeIterator: Sized {
     type Iter: Iterator<Item = Self>;
 
@@ -298,6 +760,14 @@ pub trait StaticExactSizeIterator: Sized {
     fn iter() -> Self::Iter;
 }
 
+impl StaticExactSizeIterator for Sym1 {
+    type Iter = std::iter::Once<Sym1>;
+    const COUNT: usize = crate::cube::SYM1_COUNT;
+    fn iter() -> Self::Iter {
+        std::iter::once(Sym1)
+    }
+}
+
 impl StaticExactSizeIterator for Sym16 {
     type Iter = crate::cube::Sym16Iterator;
     const COUNT: usize = crate::cube::SYM16_COUNT;
@@ -306,6 +776,14 @@ impl StaticExactSizeIterator for Sym16 {
     }
 }
 
+impl StaticExactSizeIterator for Sym48 {
+    type Iter = crate::cube::Sym48Iterator;
+    const COUNT: usize = crate::cube::SYM48_COUNT;
+    fn iter() -> Self::Iter {
+        Sym48::iter()
+    }
+}
+
 impl StaticExactSizeIterator for P2Move {
     type Iter = <Self as crate::strum::IntoEnumIterator>::Iterator;
     const COUNT: usize = P2MOVE_COUNT;
@@ -314,3 +792,307 @@ impl StaticExactSizeIterator for P2Move {
         <Self as IntoEnumIterator>::iter()
     }
 }
+
+impl StaticExactSizeIterator for Move {
+    type Iter = <Self as crate::strum::IntoEnumIterator>::Iterator;
+    const COUNT: usize = crate::cube::MOVE_COUNT;
+    fn iter() -> Self::Iter {
+        use crate::strum::IntoEnumIterator;
+        <Self as IntoEnumIterator>::iter()
+    }
+}
+
+// the tables `cubers tables build` saved in the working directory, or built in memory,
+// loaded once per test run
+#[cfg(test)]
+pub(crate) fn test_tables() -> &'static (Phase1Solver, Phase2Solver) {
+    static TABLES: std::sync::OnceLock<(Phase1Solver, Phase2Solver)> = std::sync::OnceLock::new();
+    TABLES.get_or_init(|| {
+        let p1 = std::fs::File::open("phase1.db")
+            .map(|file| Phase1Solver::new_from_mmap(&file).unwrap())
+            .unwrap_or_else(|_| Phase1Solver::build(prune::default_threads(), &mut |_| ()));
+        let p2 = std::fs::File::open("phase2.db")
+            .map(|file| Phase2Solver::new_from_mmap(&file).unwrap())
+            .unwrap_or_else(|_| Phase2Solver::build(prune::default_threads(), &mut |_| ()));
+        (p1, p2)
+    })
+}
+
+#[test]
+fn phase1() {
+    let (p1, _) = test_tables();
+
+    // building or verifying the table takes minutes, which `tables verify` is for
+    assert_eq!(table::checksum(p1.prunetable.as_bytes()), 0xb6e77d15);
+
+    assert_eq!(p1.lower_bound(Phase1Solver::SOLVED_COORD()).unwrap(), 0);
+    let coord = Phase1Coord::from(Phase1Cube::try_from(cube::SOLVED.apply(Move::F1)).unwrap());
+    assert_eq!(p1.lower_bound(coord).unwrap(), 1);
+
+    use Move::*;
+    for v in [
+        vec![R1, U1, F1],
+        vec![F1, R3, D2, L1, B1, U3],
+        vec![R1, D3, F2, L3, U1, B3, R2, D1, F3, L1, U2, B1, R3, F1, D2],
+        vec![
+            D1, L2, U3, L2, D3, B2, L2, U1, F2, D1, L2, F2, U1, D2, U2, F2, U3, D1, L2, U3, L2, D1,
+            U3, D2, L2, R2, B2,
+        ],
+    ]
+    .iter()
+    {
+        let cube = cube::CubieLevel::from_scramble(v);
+        let moves = Solver::solve(p1, cube).unwrap();
+        assert!(
+            moves.len() <= v.len().min(phase1::MAX_STEPS),
+            "{:?} {:?}",
+            v,
+            moves
+        );
+        let coord = Phase1Coord::from(Phase1Cube::try_from(cube).unwrap());
+        assert!(p1.lower_bound(coord).unwrap() as usize <= moves.len());
+        let cube = cube.apply_all(&moves);
+        assert!(Phase2Cube::try_from(cube).is_ok(), "{:?} {:?}", v, moves);
+    }
+}
+
+#[test]
+fn two_phase() {
+    let (p1, p2) = test_tables();
+    let solver = TwoPhaseSolver::new(p1.clone(), p2.clone(), 22);
+
+    use Move::*;
+    let v = vec![
+        R1, D3, F2, L3, U1, B3, R2, D1, F3, L1, U2, B1, R3, F1, D2, L2, B3, U1, R1, F2,
+    ];
+    let cube = cube::CubieLevel::from_scramble(&v);
+    let moves = Solver::solve(&solver, cube).unwrap();
+    assert!(moves.len() <= 22, "{:?}", moves);
+    assert_eq!(cube.apply_all(&moves), cube::SOLVED);
+    assert!(moves.windows(2).all(|w| !is_redundant(Some(w[0]), w[1])));
+
+    // phase 2 used to start on the face phase 1 ended with, giving "B D' F2 U R U' R R2"
+    let cube = cube::CubieLevel::from_scramble(&[R1, U1, R3, U3, F2, D1, B3]);
+    let moves = Solver::solve(&solver, cube).unwrap();
+    assert_eq!(cube.apply_all(&moves), cube::SOLVED);
+    assert!(
+        moves.windows(2).all(|w| !is_redundant(Some(w[0]), w[1])),
+        "{:?}",
+        moves
+    );
+    let cube = cube::CubieLevel::from_scramble(&v);
+
+    let checkerboard = cube::CubieLevel::from_scramble(&[U2, D2, F2, B2, L2, R2]);
+    let moves = solver.solve_between(&cube, &checkerboard).unwrap();
+    assert_eq!(cube.apply_all(&moves), checkerboard);
+    let moves = solver.solve_between(&cube::SOLVED, &checkerboard).unwrap();
+    assert_eq!(cube::SOLVED.apply_all(&moves), checkerboard);
+
+    let solver = solver.with_threads(4);
+    let moves = Solver::solve(&solver, cube).unwrap();
+    assert!(moves.len() <= 22, "{:?}", moves);
+    assert_eq!(cube.apply_all(&moves), cube::SOLVED);
+    let cubes = [cube, checkerboard, cube::SOLVED];
+    for (c, moves) in cubes
+        .iter()
+        .zip(solver.solve_all(&cubes, &SolveOptions::default()))
+    {
+        assert_eq!(c.apply_all(&moves.unwrap()), cube::SOLVED);
+    }
+
+    let mut cube = cube;
+    cube.0[0].o = (cube.0[0].o + 1) % 3;
+    assert!(matches!(
+        Solver::solve(&solver, cube),
+        Err(Error::InvalidCube(cube::VerifyError::CornerTwistSum))
+    ));
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(
+        Phase2Solver::new_from_cache(&[0u8; 16][..]),
+        Err(Error::TableMagic)
+    ));
+    assert!(matches!(
+        Phase2Solver::new_from_cache(&[1u8; 4][..]),
+        Err(Error::TableCorrupt)
+    ));
+
+    let (_, p2) = test_tables();
+    assert!(matches!(
+        Solver::solve(p2, cube::SOLVED.apply(Move::R1)),
+        Err(Error::NotInSubgroup)
+    ));
+
+    // a table only set around the goal, with 3 or a drop below 0 next to it
+    let goal = Phase1Solver::goal();
+    let size = Phase1Table::SIZES[0] * Phase1Table::SIZES[1];
+    let mut prunetable = VecU2::new(3, size as usize);
+    prunetable.set(goal, 0);
+    Phase1Table::neighbours(goal, &mut |n| prunetable.set(n, 1));
+    let p1 = Phase1Solver::from_table(prunetable);
+    assert!(matches!(
+        Solver::solve(&p1, cube::SOLVED.apply(Move::F1)),
+        Err(Error::TableCorrupt)
+    ));
+    let mut p1 = p1;
+    Phase1Table::neighbours(goal, &mut |n| p1.prunetable.set(n, 2));
+    p1.prunetable.set(goal, 0);
+    let mut all = p1.solutions(cube::SOLVED, 3).unwrap();
+    assert_eq!(all.next().unwrap().unwrap(), vec![]);
+    assert!(matches!(
+        all.find(Result::is_err),
+        Some(Err(Error::TableCorrupt))
+    ));
+    assert!(all.next().is_none());
+}
+
+#[test]
+fn solutions() {
+    use std::collections::HashSet;
+
+    let (p1, p2) = test_tables();
+
+    use Move::*;
+    let cube = cube::CubieLevel::from_scramble(&[U1, R2, D3, F2]);
+    let all: Vec<_> = p2
+        .solutions(cube, 6)
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(all[0].len(), 4);
+    assert!(all.windows(2).all(|w| w[0].len() <= w[1].len()));
+    assert_eq!(all.iter().collect::<HashSet<_>>().len(), all.len());
+    for moves in all.iter() {
+        assert!(moves.len() <= 6);
+        assert_eq!(cube.apply_all(moves), cube::SOLVED);
+    }
+    // U1 and D3 commute, but only one order is reported
+    assert!(!all.contains(&vec![F2, U1, D3, R2]) || !all.contains(&vec![F2, D3, U1, R2]));
+    assert_eq!(
+        p2.solutions(cube::SOLVED, 3)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap(),
+        vec![]
+    );
+
+    let solver = TwoPhaseSolver::new(p1.clone(), p2.clone(), 22);
+    let cube = cube::CubieLevel::from_scramble(&[R1, U1, F3, L2, D1, B1, R3]);
+    let found: Vec<_> = solver
+        .solutions(cube, 12)
+        .unwrap()
+        .take(10)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    assert_eq!(found.len(), 10);
+    assert!(found.iter().any(|moves| moves.len() == 7));
+    assert_eq!(found.iter().collect::<HashSet<_>>().len(), found.len());
+    for moves in found.iter() {
+        assert!(moves.len() <= 12);
+        assert_eq!(cube.apply_all(moves), cube::SOLVED);
+    }
+}
+
+#[test]
+fn limits() {
+    use crate::solve::CancelToken;
+    use std::time::{Duration, Instant};
+
+    let (p1, p2) = test_tables();
+    // no 20-move scramble is solved within 15 moves this quickly
+    let solver = TwoPhaseSolver::new(p1.clone(), p2.clone(), 15);
+
+    use Move::*;
+    let cube = cube::CubieLevel::from_scramble(&[
+        R1, D3, F2, L3, U1, B3, R2, D1, F3, L1, U2, B1, R3, F1, D2, L2, B3, U1, R1, F2,
+    ]);
+
+    let opts = SolveOptions {
+        max_nodes: Some(10),
+        ..Default::default()
+    };
+    assert!(matches!(
+        solver.solve_with(cube, &opts),
+        Err(Error::BudgetExceeded)
+    ));
+    // R1 then U3 is found, but it is longer than allowed
+    let (p1, p2) = test_tables();
+    let short = TwoPhaseSolver::new(p1.clone(), p2.clone(), 1);
+    assert!(matches!(
+        Solver::solve(&short, cube::CubieLevel::from_scramble(&[U1, R3])),
+        Err(Error::BudgetExceeded)
+    ));
+    // a fitting solution is kept even if another thread then runs out of nodes,
+    // and a longer one once the nodes run out
+    let best = |moves: &[Move]| Mutex::new(Some(moves.to_vec()));
+    let res = short.finish(best(&[R1]), Err(Error::BudgetExceeded));
+    assert_eq!(res.unwrap(), vec![R1]);
+    let res = short.finish(best(&[R1, U3]), Err(Error::BudgetExceeded));
+    assert_eq!(res.unwrap(), vec![R1, U3]);
+    let res = short.finish(best(&[R1, U3]), Ok(false));
+    assert!(matches!(res, Err(Error::BudgetExceeded)));
+
+    let start = Instant::now();
+    let opts = SolveOptions::with_timeout(Duration::from_millis(300));
+    let moves = solver.solve_with(cube, &opts).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(cube.apply_all(&moves), cube::SOLVED);
+
+    // another thread already has a solution no longer than this phase 1 part
+    let best = Mutex::new(Some(vec![F3]));
+    let opts = SolveOptions::default();
+    let search = Phase1Search {
+        cubie: cube::CubieLevel::from_scramble(&[F1]),
+        best: &best,
+        budget: &Budget::new(&opts),
+        thread: 0,
+        threads: 2,
+    };
+    let solved = Phase1Solver::SOLVED_COORD();
+    assert!(matches!(
+        solver.phase1_search(&search, solved, 0, 0, &mut vec![F3]),
+        Ok(true)
+    ));
+
+    let cancel = CancelToken::new();
+    cancel.cancel();
+    let opts = SolveOptions {
+        cancel: Some(cancel),
+        ..Default::default()
+    };
+    assert!(matches!(
+        solver.solve_with(cube, &opts),
+        Err(Error::Cancelled)
+    ));
+    let solver = solver.with_threads(3);
+    assert!(matches!(
+        solver.solve_with(cube, &opts),
+        Err(Error::Cancelled)
+    ));
+
+    let start = Instant::now();
+    let opts = SolveOptions::with_timeout(Duration::from_millis(300));
+    let moves = solver.solve_with(cube, &opts).unwrap();
+    assert!(start.elapsed() < Duration::from_secs(3));
+    assert_eq!(cube.apply_all(&moves), cube::SOLVED);
+    // batches are not limited to the 18 first moves
+    assert_eq!(solver.with_threads(32).threads, 32);
+
+    // the threads of one search share the node limit
+    let opts = SolveOptions {
+        max_nodes: Some(250),
+        ..Default::default()
+    };
+    let budget = Budget::new(&opts);
+    let ticked: usize = std::thread::scope(|scope| {
+        let workers: Vec<_> = (0..4)
+            .map(|_| scope.spawn(|| (0..100).filter(|_| budget.tick().is_ok()).count()))
+            .collect();
+        workers.into_iter().map(|w| w.join().unwrap()).sum()
+    });
+    assert_eq!(ticked, 250);
+    assert_eq!(budget.nodes(), 400);
+}
@@ -0,0 +1,296 @@
+use crate::solve::util::{Bytes, VecU2};
+use crate::solve::Error;
+use std::convert::TryInto;
+use std::io::{Read, Write};
+
+pub const MAGIC: [u8; 8] = *b"CUBERSPT";
+// bump whenever a coordinate encoding or the move order changes
+pub const VERSION: u32 = 2;
+pub const HEADER_LEN: usize = 48;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TableKind {
+    Phase1,
+    Phase2,
+    Transition,
+}
+
+impl TableKind {
+    // pruning tables keep depths mod 3; transition tables are plain u16 entries
+    fn modulus(self) -> u32 {
+        match self {
+            TableKind::Phase1 | TableKind::Phase2 => 3,
+            TableKind::Transition => 0,
+        }
+    }
+
+    fn payload_len(self, sizes: [u64; 2]) -> u64 {
+        match self {
+            TableKind::Phase1 | TableKind::Phase2 => (sizes[0] * sizes[1]).div_ceil(4),
+            TableKind::Transition => sizes[1] * 2,
+        }
+    }
+}
+
+impl std::fmt::Display for TableKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            TableKind::Phase1 => write!(f, "phase 1"),
+            TableKind::Phase2 => write!(f, "phase 2"),
+            TableKind::Transition => write!(f, "transition"),
+        }
+    }
+}
+
+// fixed-size little-endian header followed by the payload, e.g. packed VecU2 bytes:
+// magic, version, kind, the two coordinate sizes, modulus, crc32 of the payload, payload length
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableHeader {
+    pub version: u32,
+    pub kind: TableKind,
+    pub sizes: [u64; 2],
+    pub modulus: u32,
+    pub checksum: u32,
+    pub len: u64,
+}
+
+impl TableHeader {
+    pub fn new(kind: TableKind, sizes: [u64; 2], payload: &[u8]) -> Self {
+        TableHeader {
+            version: VERSION,
+            kind,
+            sizes,
+            modulus: kind.modulus(),
+            checksum: checksum(payload),
+            len: payload.len() as u64,
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; HEADER_LEN] {
+        let mut res = [0; HEADER_LEN];
+        res[0..8].copy_from_slice(&MAGIC);
+        res[8..12].copy_from_slice(&self.version.to_le_bytes());
+        let kind: u32 = match self.kind {
+            TableKind::Phase1 => 1,
+            TableKind::Phase2 => 2,
+            TableKind::Transition => 3,
+        };
+        res[12..16].copy_from_slice(&kind.to_le_bytes());
+        res[16..24].copy_from_slice(&self.sizes[0].to_le_bytes());
+        res[24..32].copy_from_slice(&self.sizes[1].to_le_bytes());
+        res[32..36].copy_from_slice(&self.modulus.to_le_bytes());
+        res[36..40].copy_from_slice(&self.checksum.to_le_bytes());
+        res[40..48].copy_from_slice(&self.len.to_le_bytes());
+        res
+    }
+
+    // only checks that the bytes are a header, not what it describes
+    pub fn from_bytes(bytes: &[u8; HEADER_LEN]) -> Result<Self, Error> {
+        let u32_at = |i: usize| u32::from_le_bytes(bytes[i..i + 4].try_into().unwrap());
+        let u64_at = |i: usize| u64::from_le_bytes(bytes[i..i + 8].try_into().unwrap());
+
+        if bytes[0..8] != MAGIC {
+            return Err(Error::TableMagic);
+        }
+        let kind = match u32_at(12) {
+            1 => TableKind::Phase1,
+            2 => TableKind::Phase2,
+            3 => TableKind::Transition,
+            _ => return Err(Error::TableCorrupt),
+        };
+        Ok(TableHeader {
+            version: u32_at(8),
+            kind,
+            sizes: [u64_at(16), u64_at(24)],
+            modulus: u32_at(32),
+            checksum: u32_at(36),
+            len: u64_at(40),
+        })
+    }
+
+    pub fn read_from<R: Read>(src: &mut R) -> Result<Self, Error> {
+        let mut magic = [0; 8];
+        read_exact(src, &mut magic)?;
+        if magic != MAGIC {
+            return Err(Error::TableMagic);
+        }
+        let mut bytes = [0; HEADER_LEN];
+        bytes[0..8].copy_from_slice(&magic);
+        read_exact(src, &mut bytes[8..])?;
+        Self::from_bytes(&bytes)
+    }
+
+    // checks every field against the table the caller expects
+    pub fn check(&self, kind: TableKind, sizes: [u64; 2]) -> Result<(), Error> {
+        if self.version != VERSION {
+            return Err(Error::TableVersion(self.version));
+        }
+        if self.kind != kind {
+            return Err(Error::TableKind(self.kind));
+        }
+        if self.sizes != sizes {
+            return Err(Error::TableSize(self.sizes));
+        }
+        if self.modulus != kind.modulus() {
+            return Err(Error::TableModulus(self.modulus));
+        }
+        if self.len != kind.payload_len(sizes) {
+            return Err(Error::TableCorrupt);
+        }
+        Ok(())
+    }
+}
+
+pub fn checksum(payload: &[u8]) -> u32 {
+    use crc::crc32::{Digest, Hasher32, IEEE};
+    let mut digest = Digest::new(IEEE);
+    digest.write(payload);
+    digest.sum32()
+}
+
+fn read_exact<R: Read>(src: &mut R, buf: &mut [u8]) -> Result<(), Error> {
+    src.read_exact(buf).map_err(|e| match e.kind() {
+        std::io::ErrorKind::UnexpectedEof => Error::TableCorrupt,
+        _ => Error::TableIo(e),
+    })
+}
+
+// the checked payload of a table of the given kind and sizes
+pub fn read_payload<R: Read>(
+    mut src: R,
+    kind: TableKind,
+    sizes: [u64; 2],
+) -> Result<Vec<u8>, Error> {
+    let header = TableHeader::read_from(&mut src)?;
+    header.check(kind, sizes)?;
+
+    let mut payload = vec![0; header.len as usize];
+    read_exact(&mut src, &mut payload)?;
+    let sum = checksum(&payload);
+    if sum != header.checksum {
+        return Err(Error::TableChecksum(sum));
+    }
+    Ok(payload)
+}
+
+pub fn write_payload<W: Write>(
+    mut dst: W,
+    kind: TableKind,
+    sizes: [u64; 2],
+    payload: &[u8],
+) -> Result<(), Error> {
+    let header = TableHeader::new(kind, sizes, payload);
+    dst.write_all(&header.to_bytes())?;
+    dst.write_all(payload)?;
+    dst.flush()?;
+    Ok(())
+}
+
+pub fn read_table<R: Read>(src: R, kind: TableKind, sizes: [u64; 2]) -> Result<VecU2, Error> {
+    let payload = read_payload(src, kind, sizes)?;
+    Ok(VecU2::from_bytes(
+        Bytes::Owned(payload),
+        (sizes[0] * sizes[1]) as usize,
+    ))
+}
+
+// maps the file instead of reading it, so processes share one copy of the table.
+// the payload checksum is not checked here as that would touch every page,
+// so a corrupt entry only shows up as Error::TableCorrupt once the search reaches it.
+// the file must not be truncated or rewritten in place while it is mapped
+pub fn map_table(file: &std::fs::File, kind: TableKind, sizes: [u64; 2]) -> Result<VecU2, Error> {
+    let map = unsafe { memmap2::Mmap::map(file)? };
+    if map.len() < HEADER_LEN {
+        return Err(if map.starts_with(&MAGIC[..map.len().min(8)]) {
+            Error::TableCorrupt
+        } else {
+            Error::TableMagic
+        });
+    }
+    let header = TableHeader::from_bytes(map[..HEADER_LEN].try_into().unwrap())?;
+    header.check(kind, sizes)?;
+    if map.len() as u64 != HEADER_LEN as u64 + header.len {
+        return Err(Error::TableCorrupt);
+    }
+    Ok(VecU2::from_bytes(
+        Bytes::Mapped(std::sync::Arc::new(map), HEADER_LEN),
+        (sizes[0] * sizes[1]) as usize,
+    ))
+}
+
+pub fn write_table<W: Write>(
+    dst: W,
+    kind: TableKind,
+    sizes: [u64; 2],
+    table: &VecU2,
+) -> Result<(), Error> {
+    write_payload(dst, kind, sizes, table.as_bytes())
+}
+
+#[test]
+fn header() {
+    let mut table = VecU2::new(3, 10);
+    table.set(4, 1);
+    let mut buf = vec![];
+    write_table(&mut buf, TableKind::Phase1, [2, 5], &table).unwrap();
+    assert_eq!(buf.len(), HEADER_LEN + 3);
+
+    let header = TableHeader::read_from(&mut &buf[..]).unwrap();
+    assert_eq!(
+        header,
+        TableHeader::new(TableKind::Phase1, [2, 5], table.as_bytes())
+    );
+    let res = read_table(&buf[..], TableKind::Phase1, [2, 5]).unwrap();
+    assert_eq!(res.get(4), 1);
+    assert_eq!(res.get(5), 3);
+
+    assert!(matches!(
+        read_table(&buf[..], TableKind::Phase2, [2, 5]),
+        Err(Error::TableKind(TableKind::Phase1))
+    ));
+    assert!(matches!(
+        read_table(&buf[..], TableKind::Phase1, [5, 2]),
+        Err(Error::TableSize([2, 5]))
+    ));
+    assert!(matches!(
+        read_table(&buf[..HEADER_LEN + 2], TableKind::Phase1, [2, 5]),
+        Err(Error::TableCorrupt)
+    ));
+    assert!(matches!(
+        read_table(&b"CUBERS01"[..], TableKind::Phase1, [2, 5]),
+        Err(Error::TableMagic)
+    ));
+
+    let mut bad = buf.clone();
+    bad[8] = 9;
+    assert!(matches!(
+        read_table(&bad[..], TableKind::Phase1, [2, 5]),
+        Err(Error::TableVersion(9))
+    ));
+    let mut bad = buf.clone();
+    bad[32] = 4;
+    assert!(matches!(
+        read_table(&bad[..], TableKind::Phase1, [2, 5]),
+        Err(Error::TableModulus(4))
+    ));
+    let mut bad = buf.clone();
+    bad[HEADER_LEN] ^= 1;
+    assert!(matches!(
+        read_table(&bad[..], TableKind::Phase1, [2, 5]),
+        Err(Error::TableChecksum(_))
+    ));
+
+    let path = std::env::temp_dir().join(format!("cubers-header-{}.db", std::process::id()));
+    std::fs::write(&path, &bad[..HEADER_LEN + 2]).unwrap();
+    let file = std::fs::File::open(&path).unwrap();
+    assert!(matches!(
+        map_table(&file, TableKind::Phase1, [2, 5]),
+        Err(Error::TableCorrupt)
+    ));
+    std::fs::write(&path, &buf).unwrap();
+    let file = std::fs::File::open(&path).unwrap();
+    let res = map_table(&file, TableKind::Phase1, [2, 5]).unwrap();
+    assert_eq!(res.get(4), 1);
+    assert_eq!(res.as_bytes(), table.as_bytes());
+    std::fs::remove_file(&path).unwrap();
+}
@@ -0,0 +1,100 @@
+use crate::solve::table::{self, TableKind};
+use crate::solve::util::Memo;
+use crate::solve::{phase1, phase2, Error};
+use std::io::{Read, Write};
+
+// every coordinate transition table, in the order they are saved
+fn memos() -> [&'static Memo; 20] {
+    [
+        &phase1::ctwist::MOVE_MEMO,
+        &phase1::ctwist::SYM16_MEMO,
+        &phase1::etwist::MOVE_MEMO,
+        &phase1::etwist::SYM16_MEMO,
+        &phase1::udslice::MOVE_MEMO,
+        &phase1::udslice::SYM16_MEMO,
+        &phase1::flipslice::CLASS_MEMO,
+        &phase1::flipslice::REPR_MEMO,
+        &phase1::flipslice::SYM16_MEMO,
+        &phase1::flipslice::STAB_MEMO,
+        &phase2::cperm::MOVE_MEMO,
+        &phase2::cperm::SYM16_MEMO,
+        &phase2::eperm::MOVE_MEMO,
+        &phase2::eperm::SYM16_MEMO,
+        &phase2::udslice::MOVE_MEMO,
+        &phase2::udslice::SYM16_MEMO,
+        &phase2::cpermcoset::COSET_MEMO,
+        &phase2::cpermcoset::REPR_MEMO,
+        &phase2::cpermcoset::MOVE_MEMO,
+        &phase2::SYM16_MEMO,
+    ]
+}
+
+fn sizes() -> [u64; 2] {
+    let memos = memos();
+    let total: usize = memos.iter().map(|m| m.size()).sum();
+    [memos.len() as u64, total as u64]
+}
+
+// builds every table now rather than on first use
+pub fn warm_up() {
+    for memo in memos().iter() {
+        memo.get();
+    }
+}
+
+// the move and symmetry tables of both phases, to be saved once and loaded at startup
+pub struct TransitionTables(Vec<Vec<u16>>);
+
+impl TransitionTables {
+    pub fn build() -> Self {
+        TransitionTables(memos().iter().map(|m| m.get().to_vec()).collect())
+    }
+
+    pub fn load<R: Read>(src: R) -> Result<Self, Error> {
+        let payload = table::read_payload(src, TableKind::Transition, sizes())?;
+        let mut entries = payload
+            .chunks_exact(2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]));
+        let res = memos()
+            .iter()
+            .map(|m| entries.by_ref().take(m.size()).collect())
+            .collect();
+        Ok(TransitionTables(res))
+    }
+
+    pub fn save<W: Write>(&self, dst: W) -> Result<(), Error> {
+        let payload: Vec<u8> = self
+            .0
+            .iter()
+            .flatten()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        table::write_payload(dst, TableKind::Transition, sizes(), &payload)
+    }
+
+    // makes the tables the ones used by every solver in this process;
+    // tables already built are kept
+    pub fn install(self) {
+        for (memo, table) in memos().iter().zip(self.0) {
+            memo.install(table);
+        }
+    }
+}
+
+#[test]
+fn transition() {
+    let tables = TransitionTables::build();
+    let mut buf = vec![];
+    tables.save(&mut buf).unwrap();
+    assert_eq!(buf.len() as u64, table::HEADER_LEN as u64 + sizes()[1] * 2);
+
+    let loaded = TransitionTables::load(&buf[..]).unwrap();
+    assert!(loaded.0 == tables.0);
+    loaded.install();
+
+    buf[table::HEADER_LEN + 1] ^= 1;
+    assert!(matches!(
+        TransitionTables::load(&buf[..]),
+        Err(Error::TableChecksum(_))
+    ));
+}
@@ -129,10 +129,62 @@ fn num2array2num_identity() {
     }
 }
 
-#[derive(Deserialize, Serialize)]
+// a coordinate transition table, built on first use unless installed from a saved bundle
+pub struct Memo {
+    cell: std::sync::OnceLock<Vec<u16>>,
+    size: usize,
+    build: fn() -> Vec<u16>,
+}
+
+impl Memo {
+    pub const fn new(size: usize, build: fn() -> Vec<u16>) -> Self {
+        Memo {
+            cell: std::sync::OnceLock::new(),
+            size,
+            build,
+        }
+    }
+
+    pub fn get(&self) -> &[u16] {
+        self.cell.get_or_init(|| {
+            let res = (self.build)();
+            debug_assert_eq!(res.len(), self.size);
+            res
+        })
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    // no-op once the table has been built
+    pub fn install(&self, table: Vec<u16>) {
+        debug_assert_eq!(table.len(), self.size);
+        let _ = self.cell.set(table);
+    }
+}
+
+// the packed bytes, either owned or read straight out of a mapped table file
+#[derive(Clone)]
+pub enum Bytes {
+    Owned(Vec<u8>),
+    Mapped(std::sync::Arc<memmap2::Mmap>, usize),
+}
+
+impl std::ops::Deref for Bytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Bytes::Owned(vec) => vec,
+            Bytes::Mapped(map, offset) => &map[*offset..],
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct VecU2 {
-    #[serde(with = "serde_bytes")]
-    vec: Vec<u8>,
+    vec: Bytes,
     size: u64,
 }
 
@@ -143,11 +195,27 @@ impl VecU2 {
         init |= init << 4;
 
         VecU2 {
-            vec: vec![init; (sz + 3) / 4],
+            vec: Bytes::Owned(vec![init; (sz + 3) / 4]),
+            size: sz as u64,
+        }
+    }
+
+    pub fn from_bytes(vec: Bytes, sz: usize) -> Self {
+        debug_assert_eq!(vec.len(), sz.div_ceil(4));
+        VecU2 {
+            vec,
             size: sz as u64,
         }
     }
 
+    pub fn size(&self) -> usize {
+        self.size as usize
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec
+    }
+
     pub fn get(&self, idx: usize) -> u8 {
         debug_assert!(idx < self.size as usize);
 
@@ -158,8 +226,12 @@ impl VecU2 {
     pub fn set(&mut self, idx: usize, val: u8) {
         debug_assert!(idx < self.size as usize);
 
+        let vec = match self.vec {
+            Bytes::Owned(ref mut vec) => vec,
+            Bytes::Mapped(..) => panic!("mapped tables are read-only"),
+        };
         let (u8idx, u2idx) = (idx >> 2, idx & 3);
-        self.vec[u8idx] &= !(0x3 << (u2idx << 1));
-        self.vec[u8idx] |= (val & 3) << (u2idx << 1);
+        vec[u8idx] &= !(0x3 << (u2idx << 1));
+        vec[u8idx] |= (val & 3) << (u2idx << 1);
     }
 }